@@ -1,5 +1,11 @@
+pub mod aabb;
 pub mod algorithms;
 pub mod broadphase;
 pub mod collider;
+pub mod distance;
+pub mod error;
 pub mod manifold;
 pub mod narrow;
+pub mod ray;
+pub mod support_map;
+pub mod time_of_impact;
@@ -0,0 +1,210 @@
+use crate::linalg::Vec2D;
+
+/// Axis aligned bounding box.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aabb {
+    pub min: Vec2D,
+    pub max: Vec2D,
+}
+
+impl Aabb {
+    pub fn new(min: Vec2D, max: Vec2D) -> Self {
+        Self { min, max }
+    }
+
+    /// The smallest box containing all the `points`.
+    /// *Warning*: `points` must not be empty.
+    pub fn from_points(points: &[Vec2D]) -> Self {
+        let mut res = Self::new(points[0], points[0]);
+
+        for point in &points[1..] {
+            res.min.x = res.min.x.min(point.x);
+            res.min.y = res.min.y.min(point.y);
+            res.max.x = res.max.x.max(point.x);
+            res.max.y = res.max.y.max(point.y);
+        }
+
+        res
+    }
+
+    pub fn center(&self) -> Vec2D {
+        (self.min + self.max) / 2.
+    }
+
+    /// Half of the size of the box along each axis.
+    pub fn extents(&self) -> Vec2D {
+        (self.max - self.min) / 2.
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        let size = self.max - self.min;
+        2. * (size.x + size.y)
+    }
+
+    pub fn translate(&self, offset: Vec2D) -> Self {
+        Self::new(self.min + offset, self.max + offset)
+    }
+
+    /// Grows the box by `margin` in every direction.
+    pub fn expand(&self, margin: f64) -> Self {
+        let margin = Vec2D::new(margin, margin);
+        Self::new(self.min - margin, self.max + margin)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        Self::new(
+            Vec2D::new(self.min.x.min(other.min.x), self.min.y.min(other.min.y)),
+            Vec2D::new(self.max.x.max(other.max.x), self.max.y.max(other.max.y)),
+        )
+    }
+
+    /// Touching boxes are considered overlapping.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        self.min.x <= other.max.x
+            && other.min.x <= self.max.x
+            && self.min.y <= other.max.y
+            && other.min.y <= self.max.y
+    }
+
+    /// How far apart the boxes are, 0 when they overlap. No two shapes inside them are closer.
+    pub fn distance(&self, other: &Self) -> f64 {
+        let gap_x = (other.min.x - self.max.x).max(self.min.x - other.max.x);
+        let gap_y = (other.min.y - self.max.y).max(self.min.y - other.max.y);
+
+        Vec2D::new(gap_x.max(0.), gap_y.max(0.)).norm()
+    }
+
+    /// Whether `other` is entirely inside `self`.
+    pub fn contains(&self, other: &Self) -> bool {
+        self.min.x <= other.min.x
+            && self.min.y <= other.min.y
+            && other.max.x <= self.max.x
+            && other.max.y <= self.max.y
+    }
+
+    pub fn contains_point(&self, point: Vec2D) -> bool {
+        self.min.x <= point.x
+            && point.x <= self.max.x
+            && self.min.y <= point.y
+            && point.y <= self.max.y
+    }
+
+    /// Slab test of the ray `origin + t * direction` for `t` in `[0, max_fraction]`.
+    /// Returns the smallest `t` for which the ray is inside the box.
+    ///
+    /// From: https://tavianator.com/2011/ray_box.html
+    pub fn raycast(&self, origin: Vec2D, direction: Vec2D, max_fraction: f64) -> Option<f64> {
+        let mut t_min = 0_f64;
+        let mut t_max = max_fraction;
+
+        for (o, d, min, max) in [
+            (origin.x, direction.x, self.min.x, self.max.x),
+            (origin.y, direction.y, self.min.y, self.max.y),
+        ] {
+            if d == 0. {
+                // Parallel to the slab: either always or never inside.
+                if o < min || o > max {
+                    return None;
+                }
+            } else {
+                let t1 = (min - o) / d;
+                let t2 = (max - o) / d;
+
+                t_min = t_min.max(t1.min(t2));
+                t_max = t_max.min(t1.max(t2));
+
+                if t_min > t_max {
+                    return None;
+                }
+            }
+        }
+
+        Some(t_min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{aabb::Aabb, collider::Collider},
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        let a = Aabb::new(Vec2D::new(0., 0.), Vec2D::new(2., 1.));
+        let b = Aabb::new(Vec2D::new(1., -1.), Vec2D::new(3., 0.5));
+        let c = Aabb::new(Vec2D::new(2.5, 2.), Vec2D::new(3., 3.));
+
+        assert!(a.overlaps(&b));
+        assert!(!a.overlaps(&c));
+        assert_eq!(a.perimeter(), 6.);
+
+        let u = a.union(&c);
+        assert_eq!(u, Aabb::new(Vec2D::new(0., 0.), Vec2D::new(3., 3.)));
+        assert!(u.contains(&a));
+        assert!(u.contains(&c));
+        assert!(!a.contains(&u));
+
+        assert!(a.contains_point(Vec2D::new(2., 0.5)));
+        assert!(!a.contains_point(Vec2D::new(2.1, 0.5)));
+
+        assert_eq!(a.distance(&b), 0.);
+        assert_eq!(a.distance(&c), Vec2D::new(0.5, 1.).norm());
+        assert_eq!(c.distance(&a), a.distance(&c));
+    }
+
+    #[test]
+    fn example_two() {
+        let a = Aabb::new(Vec2D::new(1., 1.), Vec2D::new(2., 2.));
+
+        assert_eq!(
+            a.raycast(Vec2D::new(0., 1.5), Vec2D::new(1., 0.), 10.),
+            Some(1.)
+        );
+        assert_eq!(
+            a.raycast(Vec2D::new(0., 1.5), Vec2D::new(1., 0.), 0.5),
+            None
+        );
+        assert_eq!(a.raycast(Vec2D::new(0., 0.), Vec2D::new(0., 1.), 10.), None);
+        assert_eq!(
+            a.raycast(Vec2D::new(0., 0.), Vec2D::new(1., 1.), 10.),
+            Some(1.)
+        );
+        // Starting inside
+        assert_eq!(
+            a.raycast(Vec2D::new(1.5, 1.5), Vec2D::new(-1., 0.), 10.),
+            Some(0.)
+        );
+    }
+
+    #[test]
+    fn example_three() {
+        let mut a = RigidBody2D::new(Vec2D::new(10., 0.), Collider::rectangle(2., 2.), 1.);
+        a.angle = std::f64::consts::FRAC_PI_4;
+
+        let aabb = a.world_aabb();
+        let half_diagonal = 2_f64.sqrt();
+
+        assert!((aabb.min - Vec2D::new(10. - half_diagonal, -half_diagonal)).near_zero());
+        assert!((aabb.max - Vec2D::new(10. + half_diagonal, half_diagonal)).near_zero());
+
+        let mut b = RigidBody2D::new(Vec2D::new(0., 0.), Collider::circle(1.), 1.);
+        b.velocity = Vec2D::new(4., 0.);
+
+        assert_eq!(
+            b.swept_aabb(0.5),
+            Aabb::new(Vec2D::new(-1., -1.), Vec2D::new(3., 1.))
+        );
+
+        // A long bar turning half a turn in place: flat at the start and at the end, but upright
+        // in between.
+        let mut c = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(10., 0.2), 1.);
+        c.angular_velocity = std::f64::consts::PI;
+
+        let swept = c.swept_aabb(1.);
+        assert!(swept.contains_point(Vec2D::new(0., 5.)));
+        assert!(swept.contains_point(Vec2D::new(0., -5.)));
+    }
+}
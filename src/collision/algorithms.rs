@@ -1,3 +1,9 @@
+pub mod clipping;
+pub mod decomposition;
 pub mod epa;
 pub mod gjk;
+pub mod heightfield;
+pub mod hull;
+pub mod mpr;
 pub mod sat;
+pub mod segment;
@@ -0,0 +1,295 @@
+// From: https://github.com/erincatto/box2d/blob/main/src/manifold.c (b2CollidePolygons)
+// And: https://dyn4j.org/2011/11/contact-points-using-clipping/
+
+use crate::{
+    collision::{algorithms::segment::segment_distance, manifold::Contact},
+    linalg::{Vec2D, TOLERANCE},
+};
+
+/// A convex polygon, or a segment, rounded by a radius: the Minkowski sum of its core and a disc.
+#[derive(Debug)]
+struct RoundedPolygon {
+    /// Counter-clockwise (with the y axis pointing up)
+    vertices: Vec<Vec2D>,
+    /// Outward normal of the edge going from `vertices[i]` to `vertices[i + 1]`
+    normals: Vec<Vec2D>,
+    radius: f64,
+}
+
+impl RoundedPolygon {
+    fn new(vertices: &[Vec2D], radius: f64) -> Self {
+        let n = vertices.len();
+        let mut vertices = vertices.to_vec();
+
+        let mut area = 0.;
+        for i in 0..n {
+            area += vertices[i].cross(vertices[(i + 1) % n]);
+        }
+        if area < 0. {
+            vertices.reverse();
+        }
+
+        let normals = (0..n)
+            .map(|i| (vertices[(i + 1) % n] - vertices[i]).right().normalize())
+            .collect();
+
+        Self {
+            vertices,
+            normals,
+            radius,
+        }
+    }
+
+    fn next(&self, index: usize) -> usize {
+        (index + 1) % self.vertices.len()
+    }
+
+    /// The edge of `self` along which `other` is the farthest, and that distance.
+    fn find_max_separation(&self, other: &Self) -> (usize, f64) {
+        let mut best_index = 0;
+        let mut max_separation = f64::NEG_INFINITY;
+
+        for (i, (normal, vertex)) in self.normals.iter().zip(&self.vertices).enumerate() {
+            let separation = other
+                .vertices
+                .iter()
+                .map(|v| *normal * (*v - *vertex))
+                .fold(f64::INFINITY, f64::min);
+
+            if separation > max_separation {
+                max_separation = separation;
+                best_index = i;
+            }
+        }
+
+        (best_index, max_separation)
+    }
+
+    /// The edge of `self` the most anti-parallel to `normal`.
+    fn find_incident_edge(&self, normal: Vec2D) -> usize {
+        let mut best_index = 0;
+        let mut min_dot = f64::INFINITY;
+
+        for (i, n) in self.normals.iter().enumerate() {
+            let dot = normal * *n;
+            if dot < min_dot {
+                min_dot = dot;
+                best_index = i;
+            }
+        }
+
+        best_index
+    }
+}
+
+/// Contact between two rounded convex polygons given by their world vertices, which can be
+/// segments (two vertices). Returns the normal, going from `a` to `b`, and up to two contact
+/// points with their penetration depth. The points are half way between the two surfaces.
+pub fn collide_rounded_polygons(
+    a_vertices: &[Vec2D],
+    a_radius: f64,
+    b_vertices: &[Vec2D],
+    b_radius: f64,
+) -> Option<Contact> {
+    let a = RoundedPolygon::new(a_vertices, a_radius);
+    let b = RoundedPolygon::new(b_vertices, b_radius);
+    let radius = a.radius + b.radius;
+
+    let (mut edge_a, separation_a) = a.find_max_separation(&b);
+    let (mut edge_b, separation_b) = b.find_max_separation(&a);
+
+    if separation_a > radius || separation_b > radius {
+        return None;
+    }
+
+    // Prefer a as the reference to avoid flickering between the two.
+    let flip = separation_b > 0.1 * TOLERANCE + separation_a;
+
+    if flip {
+        edge_a = a.find_incident_edge(b.normals[edge_b]);
+    } else {
+        edge_b = b.find_incident_edge(a.normals[edge_a]);
+    }
+
+    // The cores are apart: the closest features may be two vertices, whose contact normal isn't
+    // any of the edges' normals. Segments only have their two normals, so their ends must be
+    // checked even when the separation along those is zero. Overlapping cores are only clipped,
+    // the closest points between them telling nothing of the normal.
+    let (v11, v12) = (a.vertices[edge_a], a.vertices[a.next(edge_a)]);
+    let (v21, v22) = (b.vertices[edge_b], b.vertices[b.next(edge_b)]);
+
+    let result = segment_distance(v11, v12, v21, v22);
+    let distance = result.distance_squared.sqrt();
+
+    let is_vertex = |fraction: f64| fraction == 0. || fraction == 1.;
+
+    // Along the normal of the reference edge, as between the aligned ends of parallel edges,
+    // clipping finds the same normal and every contact point.
+    let reference_normal = if flip {
+        -b.normals[edge_b]
+    } else {
+        a.normals[edge_a]
+    };
+    let normal = (result.point_2 - result.point_1) / distance;
+
+    if separation_a.max(separation_b) > -0.1 * TOLERANCE
+        && distance > 0.1 * TOLERANCE
+        && is_vertex(result.fraction_1)
+        && is_vertex(result.fraction_2)
+        && normal * reference_normal < 1. - 0.1 * TOLERANCE
+    {
+        if distance > radius {
+            return None;
+        }
+
+        let surface_a = result.point_1 + normal * a.radius;
+        let surface_b = result.point_2 - normal * b.radius;
+
+        return Some((
+            normal,
+            vec![((surface_a + surface_b) / 2., radius - distance)],
+        ));
+    }
+
+    let (normal, points) = clip(&a, &b, edge_a, edge_b, flip);
+    let points: Vec<(Vec2D, f64)> = points.into_iter().filter(|(_, d)| *d >= 0.).collect();
+
+    if points.is_empty() {
+        None
+    } else {
+        Some((normal, points))
+    }
+}
+
+/// Clips the incident edge against the sides of the reference edge.
+fn clip(
+    a: &RoundedPolygon,
+    b: &RoundedPolygon,
+    edge_a: usize,
+    edge_b: usize,
+    flip: bool,
+) -> Contact {
+    let (reference, incident, ref_edge, inc_edge) = if flip {
+        (b, a, edge_b, edge_a)
+    } else {
+        (a, b, edge_a, edge_b)
+    };
+
+    let normal = reference.normals[ref_edge];
+    let tangent = normal.left();
+
+    let v11 = reference.vertices[ref_edge];
+    let v12 = reference.vertices[reference.next(ref_edge)];
+    let v21 = incident.vertices[inc_edge];
+    let v22 = incident.vertices[incident.next(inc_edge)];
+
+    let lower_1 = 0.;
+    let upper_1 = (v12 - v11) * tangent;
+
+    // The incident edge goes the other way round.
+    let upper_2 = (v21 - v11) * tangent;
+    let lower_2 = (v22 - v11) * tangent;
+
+    let lerp = |p: Vec2D, q: Vec2D, t: f64| p + (q - p) * t;
+
+    let v_lower = if lower_2 < lower_1 && upper_2 - lower_2 > f64::EPSILON {
+        lerp(v22, v21, (lower_1 - lower_2) / (upper_2 - lower_2))
+    } else {
+        v22
+    };
+
+    let v_upper = if upper_2 > upper_1 && upper_2 - lower_2 > f64::EPSILON {
+        lerp(v22, v21, (upper_1 - lower_2) / (upper_2 - lower_2))
+    } else {
+        v21
+    };
+
+    let radius = reference.radius + incident.radius;
+    let mut points = vec![];
+
+    for v in [v_lower, v_upper] {
+        let separation = (v - v11) * normal;
+        // Half way between the two surfaces
+        let point = v + normal * (0.5 * (reference.radius - incident.radius - separation));
+        points.push((point, radius - separation));
+    }
+
+    if flip {
+        (-normal, points)
+    } else {
+        (normal, points)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{collision::algorithms::clipping::collide_rounded_polygons, linalg::Vec2D};
+
+    fn square(center: Vec2D, half: f64) -> Vec<Vec2D> {
+        vec![
+            center + Vec2D::new(-half, -half),
+            center + Vec2D::new(half, -half),
+            center + Vec2D::new(half, half),
+            center + Vec2D::new(-half, half),
+        ]
+    }
+
+    #[test]
+    fn example_one() {
+        // A box resting on a bigger one
+        let (normal, points) = collide_rounded_polygons(
+            &square(Vec2D::zero(), 2.),
+            0.,
+            &square(Vec2D::new(0.5, 2.9), 1.),
+            0.,
+        )
+        .unwrap();
+
+        assert_eq!(normal, Vec2D::new(0., 1.));
+        assert_eq!(points.len(), 2);
+        for (point, depth) in points {
+            assert!((depth - 0.1).abs() < 1e-9);
+            assert!((point.y - 1.95).abs() < 1e-9);
+            assert!(point.x == -0.5 || point.x == 1.5);
+        }
+    }
+
+    #[test]
+    fn example_two() {
+        // Segment with a radius (capsule) lying on a box, in both orders
+        let capsule = vec![Vec2D::new(-1., 2.4), Vec2D::new(1., 2.4)];
+        let ground = square(Vec2D::zero(), 2.);
+
+        let (normal, points) = collide_rounded_polygons(&capsule, 0.5, &ground, 0.).unwrap();
+        assert_eq!(normal, Vec2D::new(0., -1.));
+        assert_eq!(points.len(), 2);
+        for (_, depth) in &points {
+            assert!((depth - 0.1).abs() < 1e-9);
+        }
+
+        let (normal, points) = collide_rounded_polygons(&ground, 0., &capsule, 0.5).unwrap();
+        assert_eq!(normal, Vec2D::new(0., 1.));
+        assert_eq!(points.len(), 2);
+
+        // Too far
+        let capsule = vec![Vec2D::new(-1., 2.6), Vec2D::new(1., 2.6)];
+        assert_eq!(collide_rounded_polygons(&capsule, 0.5, &ground, 0.), None);
+    }
+
+    #[test]
+    fn example_three() {
+        // Rounded corner against rounded corner: only touching along the diagonal
+        let a = square(Vec2D::zero(), 1.);
+        let b = square(Vec2D::new(2.5, 2.5), 1.);
+
+        let (normal, points) = collide_rounded_polygons(&a, 0.4, &b, 0.4).unwrap();
+
+        assert!((normal - Vec2D::new(1., 1.).normalize()).near_zero());
+        assert_eq!(points.len(), 1);
+        assert!((points[0].0 - Vec2D::new(1.25, 1.25)).near_zero());
+        assert!((points[0].1 - (0.8 - 0.5_f64.sqrt())).abs() < 1e-9);
+
+        // The faces would overlap on both axes, but not the rounded corners.
+        assert_eq!(collide_rounded_polygons(&a, 0.2, &b, 0.2), None);
+    }
+}
@@ -0,0 +1,359 @@
+// Ear clipping from: Computational Geometry in C, Joseph O'Rourke, 1.6
+// Then Hertel-Mehlhorn: removing the diagonals not needed for convexity gives at most four times
+// the minimal number of convex pieces.
+
+use crate::linalg::{Vec2D, TOLERANCE};
+
+/// The area of the polygon, positive when counter-clockwise (with the y axis pointing up).
+pub fn signed_area(vertices: &[Vec2D]) -> f64 {
+    let n = vertices.len();
+    (0..n)
+        .map(|i| vertices[i].cross(vertices[(i + 1) % n]))
+        .sum::<f64>()
+        / 2.
+}
+
+/// Whether the counter-clockwise polygon has no reflex vertex. Collinear vertices are accepted.
+pub fn is_convex(vertices: &[Vec2D]) -> bool {
+    let n = vertices.len();
+
+    (0..n).all(|i| {
+        let prev = vertices[(i + n - 1) % n];
+        let next = vertices[(i + 1) % n];
+        (vertices[i] - prev).cross(next - vertices[i]) >= 0.
+    })
+}
+
+/// The smallest distance between two parallel lines enclosing the convex counter-clockwise polygon.
+pub fn width(vertices: &[Vec2D]) -> f64 {
+    let n = vertices.len();
+
+    (0..n)
+        .map(|i| {
+            let inward = (vertices[(i + 1) % n] - vertices[i]).left().normalize();
+            vertices
+                .iter()
+                .map(|v| inward * (*v - vertices[i]))
+                .fold(0., f64::max)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
+
+/// Removes the vertices closer than `TOLERANCE` to the line going through their neighbours,
+/// as long as there are more than three vertices.
+pub fn remove_collinear(vertices: &mut Vec<Vec2D>) {
+    let mut i = 0;
+
+    while vertices.len() > 3 && i < vertices.len() {
+        let n = vertices.len();
+        let prev = vertices[(i + n - 1) % n];
+        let next = vertices[(i + 1) % n];
+        let distance = (next - prev).normalize().cross(vertices[i] - prev).abs();
+
+        if distance < TOLERANCE {
+            vertices.remove(i);
+            // The previous vertex may have become collinear.
+            i = i.saturating_sub(1);
+        } else {
+            i += 1;
+        }
+    }
+}
+
+fn orientation(a: Vec2D, b: Vec2D, c: Vec2D) -> f64 {
+    (b - a).cross(c - a)
+}
+
+/// Whether the segments `[p1, p2]` and `[q1, q2]` intersect, touching included.
+fn segments_intersect(p1: Vec2D, p2: Vec2D, q1: Vec2D, q2: Vec2D) -> bool {
+    let d1 = orientation(q1, q2, p1);
+    let d2 = orientation(q1, q2, p2);
+    let d3 = orientation(p1, p2, q1);
+    let d4 = orientation(p1, p2, q2);
+
+    if d1 * d2 < 0. && d3 * d4 < 0. {
+        return true;
+    }
+
+    // Collinear points lying on the other segment
+    let on_segment = |a: Vec2D, b: Vec2D, p: Vec2D| {
+        p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+    };
+
+    (d1 == 0. && on_segment(q1, q2, p1))
+        || (d2 == 0. && on_segment(q1, q2, p2))
+        || (d3 == 0. && on_segment(p1, p2, q1))
+        || (d4 == 0. && on_segment(p1, p2, q2))
+}
+
+/// Whether no two edges of the polygon cross or touch, except consecutive ones at their shared
+/// vertex, and no edge folds back onto the previous one.
+pub fn is_simple(vertices: &[Vec2D]) -> bool {
+    let n = vertices.len();
+
+    for i in 0..n {
+        let (a1, a2) = (vertices[i], vertices[(i + 1) % n]);
+        let a3 = vertices[(i + 2) % n];
+
+        // A spike: the next edge goes back along this one.
+        if (a2 - a1).cross(a3 - a2) == 0. && (a2 - a1) * (a3 - a2) < 0. {
+            return false;
+        }
+
+        for j in (i + 2)..n {
+            // The last edge shares the first vertex.
+            if i == 0 && j == n - 1 {
+                continue;
+            }
+
+            if segments_intersect(a1, a2, vertices[j], vertices[(j + 1) % n]) {
+                return false;
+            }
+        }
+    }
+
+    true
+}
+
+fn in_triangle(p: Vec2D, a: Vec2D, b: Vec2D, c: Vec2D) -> bool {
+    orientation(a, b, p) >= 0. && orientation(b, c, p) >= 0. && orientation(c, a, p) >= 0.
+}
+
+/// Triangles, as indices into `vertices`, covering the simple counter-clockwise polygon.
+pub fn triangulate(vertices: &[Vec2D]) -> Vec<[usize; 3]> {
+    let mut remaining: Vec<usize> = (0..vertices.len()).collect();
+    let mut triangles = vec![];
+
+    while remaining.len() > 3 {
+        let n = remaining.len();
+
+        let is_ear = |i: usize| {
+            let (prev, curr, next) = (
+                remaining[(i + n - 1) % n],
+                remaining[i],
+                remaining[(i + 1) % n],
+            );
+            let (a, b, c) = (vertices[prev], vertices[curr], vertices[next]);
+
+            orientation(a, b, c) > 0.
+                && remaining
+                    .iter()
+                    .filter(|j| ![prev, curr, next].contains(j))
+                    .all(|j| !in_triangle(vertices[*j], a, b, c))
+        };
+
+        // Rounding errors can leave no proper ear: clip a convex vertex anyway.
+        let ear = (0..n)
+            .find(|i| is_ear(*i))
+            .or_else(|| {
+                (0..n).find(|i| {
+                    let (a, b, c) = (
+                        vertices[remaining[(i + n - 1) % n]],
+                        vertices[remaining[*i]],
+                        vertices[remaining[(i + 1) % n]],
+                    );
+                    orientation(a, b, c) >= 0.
+                })
+            })
+            .unwrap_or(0);
+
+        triangles.push([
+            remaining[(ear + n - 1) % n],
+            remaining[ear],
+            remaining[(ear + 1) % n],
+        ]);
+        remaining.remove(ear);
+    }
+
+    triangles.push([remaining[0], remaining[1], remaining[2]]);
+
+    triangles
+}
+
+/// The polygon made of `p` and `q` glued along the edge they share, if any.
+fn merge(p: &[usize], q: &[usize]) -> Option<Vec<usize>> {
+    let (n, m) = (p.len(), q.len());
+
+    for i in 0..n {
+        let (a, b) = (p[i], p[(i + 1) % n]);
+
+        // The shared edge goes the other way round in `q`.
+        if let Some(j) = (0..m).find(|j| q[*j] == b && q[(j + 1) % m] == a) {
+            // Walk `p` from `b` back to `a`, then `q` between `a` and `b` excluded.
+            let mut merged: Vec<usize> = (1..=n).map(|k| p[(i + k) % n]).collect();
+            merged.extend((2..m).map(|k| q[(j + k) % m]));
+
+            return Some(merged);
+        }
+    }
+
+    None
+}
+
+/// Convex pieces, counter-clockwise, covering the simple counter-clockwise polygon.
+pub fn decompose(vertices: &[Vec2D]) -> Vec<Vec<Vec2D>> {
+    if is_convex(vertices) {
+        return vec![vertices.to_vec()];
+    }
+
+    let mut pieces: Vec<Vec<usize>> = triangulate(vertices)
+        .into_iter()
+        .map(|triangle| triangle.to_vec())
+        .collect();
+
+    // Remove the diagonals one at a time, as long as the pieces on each side stay convex.
+    let mut merged_any = true;
+    while merged_any {
+        merged_any = false;
+
+        'search: for i in 0..pieces.len() {
+            for j in (i + 1)..pieces.len() {
+                if let Some(merged) = merge(&pieces[i], &pieces[j]) {
+                    let polygon: Vec<Vec2D> = merged.iter().map(|k| vertices[*k]).collect();
+
+                    if is_convex(&polygon) {
+                        pieces[i] = merged;
+                        pieces.remove(j);
+                        merged_any = true;
+                        break 'search;
+                    }
+                }
+            }
+        }
+    }
+
+    pieces
+        .into_iter()
+        .map(|piece| {
+            let mut piece: Vec<Vec2D> = piece.iter().map(|k| vertices[*k]).collect();
+            remove_collinear(&mut piece);
+            piece
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::algorithms::decomposition::{
+            decompose, is_convex, is_simple, remove_collinear, signed_area, triangulate, width,
+        },
+        linalg::Vec2D,
+    };
+
+    fn l_shape() -> Vec<Vec2D> {
+        vec![
+            Vec2D::new(0., 0.),
+            Vec2D::new(3., 0.),
+            Vec2D::new(3., 1.),
+            Vec2D::new(1., 1.),
+            Vec2D::new(1., 3.),
+            Vec2D::new(0., 3.),
+        ]
+    }
+
+    #[test]
+    fn example_one() {
+        let l = l_shape();
+
+        assert_eq!(signed_area(&l), 5.);
+        assert!(!is_convex(&l));
+        assert!(is_simple(&l));
+
+        let triangles = triangulate(&l);
+        assert_eq!(triangles.len(), 4);
+
+        let area: f64 = triangles
+            .iter()
+            .map(|t| signed_area(&[l[t[0]], l[t[1]], l[t[2]]]))
+            .sum();
+        assert_eq!(area, 5.);
+
+        let pieces = decompose(&l);
+        assert_eq!(pieces.len(), 2);
+        assert!(pieces.iter().all(|p| is_convex(p)));
+        assert_eq!(pieces.iter().map(|p| signed_area(p)).sum::<f64>(), 5.);
+    }
+
+    #[test]
+    fn example_two() {
+        // A U: a convex piece for each side, and one at the bottom
+        let u = vec![
+            Vec2D::new(0., 0.),
+            Vec2D::new(3., 0.),
+            Vec2D::new(3., 3.),
+            Vec2D::new(2., 3.),
+            Vec2D::new(2., 1.),
+            Vec2D::new(1., 1.),
+            Vec2D::new(1., 3.),
+            Vec2D::new(0., 3.),
+        ];
+
+        let pieces = decompose(&u);
+        assert_eq!(pieces.len(), 3);
+        assert!(pieces.iter().all(|p| is_convex(p) && signed_area(p) > 0.));
+        assert_eq!(pieces.iter().map(|p| signed_area(p)).sum::<f64>(), 7.);
+    }
+
+    #[test]
+    fn example_three() {
+        let bowtie = vec![
+            Vec2D::new(0., 0.),
+            Vec2D::new(1., 1.),
+            Vec2D::new(1., 0.),
+            Vec2D::new(0., 1.),
+        ];
+        assert!(!is_simple(&bowtie));
+
+        // A vertex touching another edge
+        let pinched = vec![
+            Vec2D::new(0., 0.),
+            Vec2D::new(2., 0.),
+            Vec2D::new(2., 2.),
+            Vec2D::new(1., 0.),
+            Vec2D::new(0., 2.),
+        ];
+        assert!(!is_simple(&pinched));
+
+        let spike = vec![
+            Vec2D::new(0., 0.),
+            Vec2D::new(2., 0.),
+            Vec2D::new(1., 0.),
+            Vec2D::new(0., 2.),
+        ];
+        assert!(!is_simple(&spike));
+    }
+
+    #[test]
+    fn example_four() {
+        let mut square = vec![
+            Vec2D::new(0., 0.),
+            Vec2D::new(1., 0.),
+            Vec2D::new(2., 0.0005),
+            Vec2D::new(2., 1.),
+            Vec2D::new(2., 2.),
+            Vec2D::new(0., 2.),
+        ];
+        remove_collinear(&mut square);
+
+        assert_eq!(
+            square,
+            vec![
+                Vec2D::new(0., 0.),
+                Vec2D::new(2., 0.0005),
+                Vec2D::new(2., 2.),
+                Vec2D::new(0., 2.),
+            ]
+        );
+
+        assert_eq!(
+            width(&[
+                Vec2D::new(0., 0.),
+                Vec2D::new(4., 0.),
+                Vec2D::new(4., 1.),
+                Vec2D::new(0., 1.)
+            ]),
+            1.
+        );
+    }
+}
@@ -1,6 +1,28 @@
-use crate::{linalg::Vec2D, rigidbody2d::RigidBody2D};
+use crate::{
+    collision::{error::CollisionError, support_map::SupportMap},
+    linalg::{Vec2D, TOLERANCE},
+};
+
+use super::gjk::{CSOVertex, Simplex};
+
+/// How far `epa_with` expands the polytope.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EpaSettings {
+    /// The expansion stops after adding this many support points, with the closest edge found
+    /// so far.
+    pub max_iterations: u32,
+    /// The expansion stops once the support point beyond the closest edge is nearer than this.
+    pub tolerance: f64,
+}
 
-use super::gjk::{triple_product, CSOVertex, Simplex};
+impl Default for EpaSettings {
+    fn default() -> Self {
+        Self {
+            max_iterations: 100,
+            tolerance: TOLERANCE,
+        }
+    }
+}
 
 #[derive(Debug, PartialEq)]
 pub struct CollisionResult {
@@ -8,146 +30,204 @@ pub struct CollisionResult {
     pub depth: f64,
     pub point_a: Vec2D,
     pub point_b: Vec2D,
+    /// Support points added to the polytope.
+    pub iterations: u32,
+    /// Whether the depth is within the tolerance, rather than the best guess when EPA ran out of
+    /// iterations.
+    pub converged: bool,
 }
 
-fn closest_edge(simplex: &Simplex) -> (f64, usize, Vec2D) {
-    let n = simplex.len();
-    let mut min_index = 0;
-    let mut min_normal = None;
-    let mut min_dist = std::f64::INFINITY;
+/// The edge of the counter-clockwise polytope closest to the origin: its distance, the index of
+/// its first vertex, and its outward normal.
+fn closest_edge(polytope: &[CSOVertex]) -> Result<(f64, usize, Vec2D), CollisionError> {
+    let n = polytope.len();
+    let mut closest = None;
+    let mut min_dist = f64::INFINITY;
 
     for i in 0..n {
         let j = (i + 1) % n;
-        // println!(
-        //     "Looking at edge from {i}: {} and {j}: {}",
-        //     simplex[i].to_vec(),
-        //     simplex[j].to_vec()
-        // );
-        let edge = simplex[j].to_vec() - simplex[i].to_vec();
-
-        if !edge.is_correct() {
-            panic!("Stop: edge is incorrect.");
+        let edge = polytope[j].to_vec() - polytope[i].to_vec();
+
+        if edge.near_zero() {
+            continue;
         }
-        // let normal = if simplex[i].to_vec().cross(simplex[j].to_vec()) < 0. {
-        //     Vec2D::new(-edge.y, edge.x)
-        // } else {
-        //     Vec2D::new(edge.y, -edge.x)
-        // }
-        // .normalize();
 
-        let normal = triple_product(edge, simplex[i].to_vec(), edge).normalize();
+        // Unlike a triple product, still defined when the origin is on the edge.
+        let normal = edge.right().normalize();
+        let dist = normal * polytope[i].to_vec();
 
-        if normal.near_zero() || !normal.is_correct() {
-            // clg!("Triple prodcut was bad.");
-            continue;
-            // normal = if simplex[i].to_vec().cross(simplex[j].to_vec()) > 0. {
-            //     Vec2D::new(-edge.y, edge.x)
-            // } else {
-            //     Vec2D::new(edge.y, -edge.x)
-            // }
-            // .normalize();
-            // normal = edge.right();
-
-            // println!("With edge: {edge} we computed this normal: {normal}");
+        if dist < min_dist {
+            min_dist = dist;
+            closest = Some((i, normal));
         }
+    }
 
-        if !normal.is_correct() {
-            panic!("Stop: normal is incorrect.");
+    let (index, normal) = closest.ok_or(CollisionError::DegenerateSimplex)?;
+
+    Ok((min_dist, index, normal))
+}
+
+fn is_duplicate(points: &[CSOVertex], point: CSOVertex) -> bool {
+    points
+        .iter()
+        .any(|p| (p.to_vec() - point.to_vec()).near_zero())
+}
+
+/// Turns the simplex containing the origin into a counter-clockwise triangle, adding support
+/// points when GJK stopped at a point or a segment. `Err` with what is left when the Minkowski
+/// difference is flat, the shapes then only touching.
+fn polytope(
+    simplex: Simplex,
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+) -> Result<Result<Vec<CSOVertex>, Vec<CSOVertex>>, CollisionError> {
+    let mut points: Vec<CSOVertex> = vec![];
+    for point in simplex.points {
+        if !is_duplicate(&points, point) {
+            points.push(point);
         }
+    }
+
+    if points.is_empty() {
+        return Err(CollisionError::DegenerateSimplex);
+    }
 
-        if !simplex[i].to_vec().is_correct() {
-            panic!("Stop: simplex edge is incorect.");
+    if points.len() == 1 {
+        for dir in [
+            Vec2D::new(1., 0.),
+            Vec2D::new(-1., 0.),
+            Vec2D::new(0., 1.),
+            Vec2D::new(0., -1.),
+        ] {
+            let support = CSOVertex::get(a, b, dir)?;
+            if !is_duplicate(&points, support) {
+                points.push(support);
+                break;
+            }
         }
+    }
 
-        // let normal = triple_product(edge, simplex[i].to_vec(), edge).normalize();
-        let dist = (normal * (simplex[i].to_vec())).abs();
-        // println!("Got dist: {dist}");
-        if dist < min_dist {
-            min_dist = dist;
-            min_index = i;
-            min_normal = Some(normal);
+    if points.len() == 2 {
+        let edge = (points[1].to_vec() - points[0].to_vec()).normalize();
+
+        for dir in [edge.left(), edge.right()] {
+            let support = CSOVertex::get(a, b, dir)?;
+            if (edge.cross(support.to_vec() - points[0].to_vec())).abs() > TOLERANCE {
+                points.push(support);
+                break;
+            }
         }
     }
 
+    if points.len() < 3 {
+        return Ok(Err(points));
+    }
+
+    let (p0, p1, p2) = (points[0].to_vec(), points[1].to_vec(), points[2].to_vec());
+    let area = (p1 - p0).cross(p2 - p0);
+
+    if area.abs() < TOLERANCE * TOLERANCE {
+        return Ok(Err(points));
+    }
+
+    if area < 0. {
+        points.swap(1, 2);
+    }
+
+    Ok(Ok(points))
+}
+
+/// The points of each shape whose difference is the closest point to the origin on `[v1, v2]`.
+pub fn witness_points(v1: CSOVertex, v2: CSOVertex) -> (Vec2D, Vec2D) {
+    let (p1, p2) = (v1.to_vec(), v2.to_vec());
+    let l = p2 - p1;
+
+    if l.near_zero() {
+        return (v1.a, v1.b);
+    }
+
+    let lambda = (-(p1 * l) / l.squared_norm()).clamp(0., 1.);
+
     (
-        min_dist,
-        min_index,
-        min_normal.expect(format!("Can't find the closest edge, simplex: {:#?}", simplex).as_str()),
+        (1. - lambda) * v1.a + lambda * v2.a,
+        (1. - lambda) * v1.b + lambda * v2.b,
     )
 }
 
-const EPA_ITERATIONS: u32 = 100;
-
-pub fn epa(mut simplex: Simplex, a: &RigidBody2D, b: &RigidBody2D) -> CollisionResult {
-    // let mut winding = 0;
-    // for i in 0..simplex.len() {
-    //     let j = if i + 1 == simplex.len() { 0 } else { i + 1 };
-    //     let a = simplex[i].to_vec();
-    //     let b = simplex[j].to_vec();
-    //     let cross = a.x * b.y - a.y * b.x;
-    //     if cross > 0. {
-    //         winding = 1;
-    //         break;
-    //     } else if cross < 0. {
-    //         winding = -1;
-    //         break;
-    //     }
-    // }
+/// EPA with the default settings, see `epa_with`.
+pub fn epa(
+    simplex: Simplex,
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+) -> Result<CollisionResult, CollisionError> {
+    epa_with(simplex, a, b, EpaSettings::default())
+}
 
-    // println!("Winding: {winding}");
-
-    // clg!("Iteration 0:");
-    // clg!("a: {:#?}\nb: {:#?}\nsimplex: {:#?}", a, b, simplex);
-    for _ in 0..EPA_ITERATIONS {
-        let (depth, index, normal) = closest_edge(&simplex);
-        let support = CSOVertex::get(a, b, normal);
-        let r = support.to_vec();
-        // clg!("Iteration {}: adding support {}", i + 1, r);
-        // clg!("a: {:#?}\nb: {:#?}\nsimplex: {:#?}", a, b, simplex);
-        if ((normal * r) - depth).abs() < 0.001 {
-            let jndex = (index + 1) % simplex.len();
-            let a = simplex[index].to_vec();
-            let b = simplex[jndex].to_vec();
-
-            let l = b - a;
-
-            let mut point_a = simplex[index].a;
-            let mut point_b = simplex[index].b;
-
-            if l.near_zero() {
-                return CollisionResult {
-                    normal,
-                    depth,
-                    point_a,
-                    point_b,
-                };
-            }
+/// Penetration of two overlapping shapes from the simplex `gjk_collision` found: the normal,
+/// pointing from `b` to `a`, the depth, and the deepest point of each shape.
+///
+/// Shapes only touching, whose Minkowski difference has the origin on its boundary, give a zero
+/// depth. When the expansion runs out of iterations, the closest edge found so far is returned
+/// with `converged` unset.
+///
+/// From: https://dyn4j.org/2010/05/epa-expanding-polytope-algorithm/
+pub fn epa_with(
+    simplex: Simplex,
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+    settings: EpaSettings,
+) -> Result<CollisionResult, CollisionError> {
+    let mut polytope = match polytope(simplex, a, b)? {
+        Ok(polytope) => polytope,
+        Err(points) => {
+            // Touching: the origin is on the flat Minkowski difference.
+            let normal = match points.as_slice() {
+                [p1, p2, ..] => (p2.to_vec() - p1.to_vec()).right().normalize(),
+                _ => Vec2D::new(0., 1.),
+            };
+            let (point_a, point_b) =
+                witness_points(points[0], *points.get(1).unwrap_or(&points[0]));
 
-            let lambda = -(a * l) / l.squared_norm();
+            return Ok(CollisionResult {
+                normal,
+                depth: 0.,
+                point_a,
+                point_b,
+                iterations: 0,
+                converged: true,
+            });
+        }
+    };
 
-            if 1. - lambda < 0. {
-                point_a = simplex[jndex].a;
-                point_b = simplex[jndex].b;
-            } else if lambda > 0. {
-                point_a = (1. - lambda) * simplex[index].a + lambda * simplex[jndex].a;
-                point_b = (1. - lambda) * simplex[index].b + lambda * simplex[jndex].b;
-            }
+    let mut iterations = 0;
 
-            // println!("concluded in {i} steps");
-            return CollisionResult {
+    loop {
+        let (depth, index, normal) = closest_edge(&polytope)?;
+        let next = (index + 1) % polytope.len();
+
+        let support = CSOVertex::get(a, b, normal)?;
+
+        // A support point already in the polytope can't expand it any more, which happens with
+        // corners touching.
+        let converged = support.to_vec() * normal - depth < settings.tolerance
+            || is_duplicate(&polytope, support);
+
+        if converged || iterations == settings.max_iterations {
+            let (point_a, point_b) = witness_points(polytope[index], polytope[next]);
+
+            return Ok(CollisionResult {
                 normal: -normal,
-                depth,
+                depth: depth.max(0.),
                 point_a,
                 point_b,
-            };
+                iterations,
+                converged,
+            });
         }
-        simplex.insert(index + 1, support);
-    }
 
-    panic!(
-        "Epa runned out of iterations. \nTo reproduce: a: {:#?}\nb:{:#?}",
-        a, b
-    );
+        polytope.insert(index + 1, support);
+        iterations += 1;
+    }
 }
 
 #[cfg(test)]
@@ -156,8 +236,11 @@ mod tests {
 
     use crate::{
         collision::{
-            algorithms::{epa::CollisionResult, gjk::gjk_collision},
-            collider::Collider::{CircleCollider, PolygonCollider},
+            algorithms::{
+                epa::{epa_with, CollisionResult, EpaSettings},
+                gjk::{gjk_collision, CSOVertex, Simplex},
+            },
+            collider::Collider::{self, CircleCollider, PolygonCollider},
         },
         linalg::Vec2D,
         rigidbody2d::RigidBody2D,
@@ -165,6 +248,7 @@ mod tests {
 
     use super::epa;
 
+    /// Touching along y = 200
     #[test]
     fn example_one() {
         let a = RigidBody2D {
@@ -179,6 +263,7 @@ mod tests {
                     Vec2D { x: 50.0, y: 50.0 },
                     Vec2D { x: 50.0, y: -50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -200,6 +285,7 @@ mod tests {
                     Vec2D { x: 100.0, y: 35.0 },
                     Vec2D { x: 100.0, y: -35.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -208,23 +294,25 @@ mod tests {
 
         println!("===== GJK =====");
         let simplex = match gjk_collision(&a, &b) {
-            Some(s) => s,
-            None => panic!("GJK didn't get a collision."),
+            Ok(Some(s)) => s,
+            _ => panic!("GJK didn't get a collision."),
         };
 
         println!("===== SPL =====");
         println!("{:#?}", simplex);
 
         println!("===== EPA =====");
-        let result = epa(simplex, &a, &b);
+        let result = epa(simplex, &a, &b).unwrap();
 
         assert_eq!(
             result,
             CollisionResult {
-                normal: Vec2D { x: 1.0, y: -0.0 },
-                depth: 150.0,
+                normal: Vec2D { x: 0.0, y: 1.0 },
+                depth: 0.0,
                 point_a: Vec2D { x: 350.0, y: 200.0 },
-                point_b: Vec2D { x: 500.0, y: 200.0 }
+                point_b: Vec2D { x: 350.0, y: 200.0 },
+                iterations: result.iterations,
+                converged: true,
             }
         )
     }
@@ -243,6 +331,7 @@ mod tests {
                     Vec2D { x: 50.0, y: 50.0 },
                     Vec2D { x: 50.0, y: -50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -264,6 +353,7 @@ mod tests {
                     Vec2D { x: 100.0, y: 50.0 },
                     Vec2D { x: 100.0, y: -50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -271,18 +361,20 @@ mod tests {
         };
 
         let simplex = match gjk_collision(&a, &b) {
-            Some(s) => s,
-            None => panic!("GJK didn't get a collision."),
+            Ok(Some(s)) => s,
+            _ => panic!("GJK didn't get a collision."),
         };
 
-        let result = epa(simplex, &a, &b);
+        let result = epa(simplex, &a, &b).unwrap();
         assert_eq!(
             result,
             CollisionResult {
-                normal: Vec2D { x: -0.0, y: 1.0 },
-                depth: 104.0,
-                point_a: Vec2D { x: 402.0, y: 200.0 },
-                point_b: Vec2D { x: 402.0, y: 304.0 }
+                normal: Vec2D { x: 0.0, y: -1.0 },
+                depth: 96.0,
+                point_a: Vec2D { x: 402.0, y: 300.0 },
+                point_b: Vec2D { x: 402.0, y: 204.0 },
+                iterations: result.iterations,
+                converged: true,
             }
         )
     }
@@ -315,6 +407,7 @@ mod tests {
                     Vec2D { x: 100.0, y: 50.0 },
                     Vec2D { x: 100.0, y: -50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -323,23 +416,23 @@ mod tests {
 
         println!("===== GJK =====");
         let simplex = match gjk_collision(&a, &b) {
-            Some(s) => s,
-            None => panic!("GJK didn't get a collision."),
+            Ok(Some(s)) => s,
+            _ => panic!("GJK didn't get a collision."),
         };
 
         println!("===== SPL =====");
         println!("{:#?}", simplex);
 
         println!("===== EPA =====");
-        let result = epa(simplex, &a, &b);
+        let result = epa(simplex, &a, &b).unwrap();
         assert_eq!(
             result,
             CollisionResult {
                 normal: Vec2D {
-                    x: -1.0825472913881718e-6,
+                    x: -1.0825472914186212e-6,
                     y: 0.999999999999414
                 },
-                depth: 127.99988572773604,
+                depth: 127.99988572773603,
                 point_a: Vec2D {
                     x: 400.089269166606,
                     y: 170.00011427233898
@@ -347,7 +440,9 @@ mod tests {
                 point_b: Vec2D {
                     x: 400.0891306006764,
                     y: 298.0
-                }
+                },
+                iterations: result.iterations,
+                converged: true,
             }
         )
     }
@@ -380,6 +475,7 @@ mod tests {
                     Vec2D { x: 100.0, y: 50.0 },
                     Vec2D { x: -100.0, y: 50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -388,8 +484,8 @@ mod tests {
 
         println!("===== GJK =====");
         let simplex = match gjk_collision(&a, &b) {
-            Some(s) => s,
-            None => panic!("GJK didn't get a collision."),
+            Ok(Some(s)) => s,
+            _ => panic!("GJK didn't get a collision."),
         };
 
         println!("===== SPL =====");
@@ -398,26 +494,91 @@ mod tests {
         // clg!("{:#?}", simplex);
 
         println!("===== EPA =====");
-        let result = epa(simplex, &a, &b);
+        let result = epa(simplex, &a, &b).unwrap();
         assert_eq!(
             result,
             CollisionResult {
                 normal: Vec2D {
-                    x: -4.736703088154728e-9,
+                    x: -4.736703280176777e-9,
                     y: -1.0
                 },
-                depth: 78.99999910497503,
+                depth: 78.999999104975,
                 point_a: Vec2D {
-                    x: 400.0063376179095,
+                    x: 400.00633761790954,
                     y: 329.999999104975
                 },
                 point_b: Vec2D {
                     x: 400.00633724370994,
                     y: 251.0
-                }
+                },
+                iterations: result.iterations,
+                converged: true,
             }
         )
     }
+
+    /// Rotated squares touching, or barely overlapping, at their corners
+    #[test]
+    fn example_five() {
+        let mut a = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
+        a.angle = std::f64::consts::FRAC_PI_4;
+
+        for overlap in [0., 1e-12, 1e-6, 1e-3] {
+            let mut b = RigidBody2D::new(
+                Vec2D::new(2. * std::f64::consts::SQRT_2 - overlap, 0.),
+                Collider::rectangle(2., 2.),
+                1.,
+            );
+            b.angle = std::f64::consts::FRAC_PI_4;
+
+            if let Some(simplex) = gjk_collision(&a, &b).unwrap() {
+                let result = epa(simplex, &a, &b).unwrap();
+
+                assert!(result.converged);
+                assert!(result.depth >= 0. && result.depth <= overlap + 1e-3);
+            }
+        }
+    }
+
+    /// GJK stopping at a segment or a point through the origin
+    #[test]
+    fn example_six() {
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(0.5, 0.), Collider::circle(1.), 1.);
+
+        let right = CSOVertex::get(&a, &b, Vec2D::new(1., 0.)).unwrap();
+        let left = CSOVertex::get(&a, &b, Vec2D::new(-1., 0.)).unwrap();
+
+        let line = Simplex {
+            points: vec![right, left],
+        };
+        let result = epa(line, &a, &b).unwrap();
+
+        assert!(result.converged);
+        assert!((result.depth - 1.5).abs() < 1e-2);
+        assert!(result.normal * Vec2D::new(-1., 0.) > 0.99);
+
+        // Touching circles: the support point is the origin.
+        let c = RigidBody2D::new(Vec2D::new(2., 0.), Collider::circle(1.), 1.);
+        let point = Simplex {
+            points: vec![CSOVertex::get(&a, &c, Vec2D::new(1., 0.)).unwrap()],
+        };
+        let result = epa(point, &a, &c).unwrap();
+        assert!(result.converged);
+        assert!(result.depth < 1e-3);
+
+        // Stopped before converging
+        let line = Simplex {
+            points: vec![right, left],
+        };
+        let settings = EpaSettings {
+            max_iterations: 0,
+            ..EpaSettings::default()
+        };
+        let result = epa_with(line, &a, &b, settings).unwrap();
+        assert!(!result.converged);
+        assert_eq!(result.iterations, 0);
+    }
     // #[test]
     // fn example_four() {
     //     let a = RigidBody2D {
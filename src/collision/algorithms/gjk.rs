@@ -1,9 +1,8 @@
-use core::panic;
 use std::ops::{Index, IndexMut};
 
 use crate::{
+    collision::{distance::Distance, error::CollisionError, ray::RayHit, support_map::SupportMap},
     linalg::{Vec2D, TOLERANCE},
-    rigidbody2d::RigidBody2D,
 };
 
 // use wasm_bindgen::prelude::wasm_bindgen;
@@ -18,14 +17,6 @@ use crate::{
 //     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
 // }
 
-pub fn support_point(body: &RigidBody2D, dir: Vec2D) -> Vec2D {
-    let local_dir = body.to_local(dir);
-
-    let local_support = body.collider.support(local_dir);
-
-    body.to_global(local_support)
-}
-
 pub fn triple_product(a: Vec2D, b: Vec2D, c: Vec2D) -> Vec2D {
     // let dot = a.x * b.y - b.x * a.y;
     // Vec2D::new(-c.y * dot, c.y * dot)
@@ -43,20 +34,17 @@ impl CSOVertex {
         Self { a, b }
     }
 
-    pub fn get(a: &RigidBody2D, b: &RigidBody2D, dir: Vec2D) -> Self {
-        let supp_a = support_point(a, dir);
-        let supp_b = support_point(b, -dir);
+    pub fn get(a: &dyn SupportMap, b: &dyn SupportMap, dir: Vec2D) -> Result<Self, CollisionError> {
+        let supp_a = a.support(dir);
+        let supp_b = b.support(-dir);
 
         let res = Self::new(supp_a, supp_b);
 
         if !res.is_corect() {
-            panic!(
-                "Incorrect point generated.\nFor reproduction: a: {:#?}\nb: {:#?}\ndir:{dir}",
-                a, b
-            )
+            return Err(CollisionError::InvalidSupportPoint);
         }
 
-        res
+        Ok(res)
     }
 
     pub fn to_vec(&self) -> Vec2D {
@@ -107,7 +95,12 @@ impl Simplex {
         self.points.insert(index, point)
     }
 
-    fn handle_line_case(&mut self, a: &RigidBody2D, b: &RigidBody2D, dir: &mut Vec2D) -> bool {
+    fn handle_line_case(
+        &mut self,
+        a: &dyn SupportMap,
+        b: &dyn SupportMap,
+        dir: &mut Vec2D,
+    ) -> Result<bool, CollisionError> {
         let point_b = self.points[0].to_vec();
         let point_a = self.points[1].to_vec();
 
@@ -117,21 +110,20 @@ impl Simplex {
         // println!("ab: {ab}, ao: {ao}");
 
         if (point_a.norm() + (-point_b).norm() - ab.norm()).abs() < TOLERANCE {
-            println!("Origin lies on the edge.");
             let left_normal = Vec2D::new(ab.y, -ab.x).normalize();
             let right_normal = Vec2D::new(-ab.y, ab.x).normalize();
 
-            let left_support = CSOVertex::get(a, b, left_normal);
-            let right_support = CSOVertex::get(a, b, right_normal);
+            let left_support = CSOVertex::get(a, b, left_normal)?;
+            let right_support = CSOVertex::get(a, b, right_normal)?;
 
             if left_support != self.points[0] && left_support != self.points[1] {
                 self.add(left_support);
-                return true;
+                return Ok(true);
             } else if right_support != self.points[0] && right_support != self.points[1] {
                 self.add(right_support);
-                return true;
+                return Ok(true);
             } else {
-                panic!("Denegerate case ?");
+                return Err(CollisionError::DegenerateSimplex);
             }
         }
 
@@ -169,7 +161,7 @@ impl Simplex {
             *dir = ao.normalize();
         }
 
-        false
+        Ok(false)
     }
 
     fn handle_triangle_case(&mut self, dir: &mut Vec2D) -> bool {
@@ -212,11 +204,16 @@ impl Simplex {
         }
     }
 
-    pub fn handle(&mut self, a: &RigidBody2D, b: &RigidBody2D, dir: &mut Vec2D) -> bool {
+    pub fn handle(
+        &mut self,
+        a: &dyn SupportMap,
+        b: &dyn SupportMap,
+        dir: &mut Vec2D,
+    ) -> Result<bool, CollisionError> {
         if self.points.len() == 2 {
             self.handle_line_case(a, b, dir)
         } else {
-            self.handle_triangle_case(dir)
+            Ok(self.handle_triangle_case(dir))
         }
     }
 
@@ -251,11 +248,14 @@ impl IndexMut<usize> for Simplex {
 
 const GJK_ITERATIONS: u32 = 1000;
 
-/// Check if two rigid bodies intersects
+/// Check if two convex shapes intersect, bodies included
 ///
 /// From: See README.md for reference
-pub fn gjk_collision(a: &RigidBody2D, b: &RigidBody2D) -> Option<Simplex> {
-    let mut dir = (b.position - a.position).normalize();
+pub fn gjk_collision(
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+) -> Result<Option<Simplex>, CollisionError> {
+    let mut dir = (b.center() - a.center()).normalize();
     if dir.near_zero() || !dir.is_correct() {
         dir = Vec2D::new(1., 0.);
     }
@@ -263,7 +263,7 @@ pub fn gjk_collision(a: &RigidBody2D, b: &RigidBody2D) -> Option<Simplex> {
 
     // println!("Initial dir: {dir}");
 
-    let support = CSOVertex::get(a, b, dir);
+    let support = CSOVertex::get(a, b, dir)?;
     let mut simplex = Simplex::new();
     simplex.add(support);
 
@@ -272,10 +272,7 @@ pub fn gjk_collision(a: &RigidBody2D, b: &RigidBody2D) -> Option<Simplex> {
     dir = -support.to_vec().normalize();
 
     if dir.near_zero() || !dir.is_correct() {
-        panic!(
-            "Dir is zero or incorrect.\nFor reproduction: a: {:#?}\nb: {:#?}\nsimplex: {:#?}",
-            a, b, simplex
-        );
+        return Err(CollisionError::DegenerateDirection);
     }
 
     for _ in 0..GJK_ITERATIONS {
@@ -285,89 +282,351 @@ pub fn gjk_collision(a: &RigidBody2D, b: &RigidBody2D) -> Option<Simplex> {
         // clg!("Le simplex: {:#?}", simplex);
         // clg!("La direction: {}", dir);
         if dir.near_zero() || !dir.is_correct() {
-            panic!(
-                "Dir is zero or incorrect.\nFor reproduction: a: {:#?}\nb: {:#?}\nsimplex: {:#?}",
-                a, b, simplex
-            );
+            return Err(CollisionError::DegenerateDirection);
         }
 
-        let support = CSOVertex::get(a, b, dir);
+        let support = CSOVertex::get(a, b, dir)?;
         // println!("Got support {} with direction {}", support.to_vec(), dir);
         let point_a = support.to_vec();
 
         if point_a * dir < 0. {
-            return None;
+            return Ok(None);
         }
 
         simplex.add(support);
 
         // clg!("Le simplex avant handle: {:#?}", simplex);
 
-        if simplex.handle(a, b, &mut dir) {
+        if simplex.handle(a, b, &mut dir)? {
             // panic!("attends un peu");
-            return Some(simplex);
+            return Ok(Some(simplex));
         }
     }
 
-    // clg!("Runned out of iterations.");
-    None
+    Err(CollisionError::NoConvergence)
 }
 
-/// Compute the distance and the closest points between to rigid bodies.
-/// *Warning*: The two bodies must not intersects, otherwise the result may be incorrect.
-///
-/// From: https://github.com/RayzRazko/gjk-js/blob/master/lib/gjk.js#L138
-/// And: https://dyn4j.org/2010/04/gjk-distance-closest-points/
-pub fn gjk_distance(a: &RigidBody2D, b: &RigidBody2D) -> Option<(f64, Vec2D, Vec2D)> {
-    let mut dir = (a.position - b.position).normalize();
+/// Where two convex shapes stand relative to each other, as found by `gjk_proximity`.
+#[derive(Debug)]
+pub enum Proximity {
+    /// Touching or overlapping: the simplex, around the origin, is the start of EPA.
+    Overlapping(Simplex),
+    /// Further apart than the distance looked for.
+    Beyond,
+    Separated(Distance),
+}
 
-    let mut simplex = Simplex::new();
+/// Relative precision on the distance between two shapes.
+const DISTANCE_TOLERANCE: f64 = 1e-9;
 
-    simplex.add(CSOVertex::get(a, b, dir));
+/// The closest points of two convex shapes, stopping as soon as they are known to be further
+/// apart than `max_distance`.
+///
+/// From: https://github.com/erincatto/box2d/blob/main/src/collision/b2_distance.cpp
+pub fn gjk_proximity(
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+    max_distance: f64,
+) -> Result<Proximity, CollisionError> {
+    // Toward the origin, from the center of the Minkowski difference
+    let mut dir = b.center() - a.center();
+    if dir.near_zero() || !dir.is_correct() {
+        dir = Vec2D::new(1., 0.);
+    }
 
-    simplex.add(CSOVertex::get(a, b, -dir));
+    let mut simplex = WeightedSimplex::default();
+    simplex.add(CSOVertex::get(a, b, dir)?);
+    let mut v = simplex.closest_point();
 
     for _ in 0..GJK_ITERATIONS {
-        let p = simplex.closest_point_on_segment_to_origin();
+        let distance = v.norm();
 
-        if p.near_zero() {
-            return None;
+        if distance <= DISTANCE_TOLERANCE {
+            return Ok(Proximity::Overlapping(simplex.to_simplex()));
         }
 
-        dir = (-p).normalize();
+        let support = CSOVertex::get(a, b, -v)?;
+
+        // No point of the Minkowski difference is beyond the plane through the support point.
+        let lower_bound = (support.to_vec() * v) / distance;
 
-        let support = CSOVertex::get(a, b, dir);
-        let point_a = simplex[0].to_vec();
-        let point_b = simplex[1].to_vec();
-        let point_c = support.to_vec();
+        if lower_bound > max_distance {
+            return Ok(Proximity::Beyond);
+        }
 
-        if ((point_c * dir) - (point_a * dir)).abs() < TOLERANCE {
-            let t = get_barycentric_coord_origin(point_a, point_b);
+        let is_duplicate = simplex.vertices.iter().any(|w| w.vertex == support);
 
-            return Some((
-                -point_c * dir,
-                (1. - t) * simplex[0].a + t * simplex[1].a,
-                (1. - t) * simplex[0].b + t * simplex[1].b,
-            ));
+        if is_duplicate || distance - lower_bound <= DISTANCE_TOLERANCE * distance.max(1.) {
+            let (point_a, point_b) = simplex.witness_points();
+
+            return Ok(Proximity::Separated(Distance {
+                separation: distance,
+                point_a,
+                point_b,
+                normal: -v / distance,
+            }));
         }
 
-        if point_a.squared_norm() < point_b.squared_norm() {
-            simplex[1] = support;
+        simplex.add(support);
+        simplex.solve();
+
+        if simplex.len() == 3 {
+            // The origin is inside the Minkowski difference.
+            return Ok(Proximity::Overlapping(simplex.to_simplex()));
+        }
+
+        v = simplex.closest_point();
+    }
+
+    Err(CollisionError::NoConvergence)
+}
+
+/// The distance and the closest points between two convex shapes, `None` when they touch or
+/// overlap.
+pub fn gjk_distance(
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+) -> Result<Option<Distance>, CollisionError> {
+    match gjk_proximity(a, b, f64::INFINITY)? {
+        Proximity::Separated(distance) => Ok(Some(distance)),
+        Proximity::Overlapping(_) | Proximity::Beyond => Ok(None),
+    }
+}
+
+/// A vertex of a `WeightedSimplex` with its barycentric coordinate.
+#[derive(Debug, Clone, Copy)]
+struct WeightedVertex {
+    vertex: CSOVertex,
+    weight: f64,
+}
+
+/// Simplex keeping the barycentric coordinates of its closest point to the origin,
+/// needed to get back the witness points on each shape.
+///
+/// From: https://github.com/erincatto/box2d/blob/main/src/collision/b2_distance.cpp
+#[derive(Debug, Default)]
+struct WeightedSimplex {
+    vertices: Vec<WeightedVertex>,
+}
+
+impl WeightedSimplex {
+    fn add(&mut self, vertex: CSOVertex) {
+        self.vertices.push(WeightedVertex { vertex, weight: 1. });
+    }
+
+    fn clear(&mut self) {
+        self.vertices.clear();
+    }
+
+    fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    /// Reduces the simplex to the feature closest to the origin, and computes the weights.
+    fn solve(&mut self) {
+        match self.vertices.len() {
+            2 => self.solve_line(),
+            3 => self.solve_triangle(),
+            _ => (),
+        }
+    }
+
+    fn keep(&mut self, weighted: &[(usize, f64)]) {
+        self.vertices = weighted
+            .iter()
+            .map(|&(index, weight)| WeightedVertex {
+                vertex: self.vertices[index].vertex,
+                weight,
+            })
+            .collect();
+    }
+
+    fn solve_line(&mut self) {
+        let w1 = self.vertices[0].vertex.to_vec();
+        let w2 = self.vertices[1].vertex.to_vec();
+        let e12 = w2 - w1;
+
+        let d12_2 = -(w1 * e12);
+        if d12_2 <= 0. {
+            return self.keep(&[(0, 1.)]);
+        }
+
+        let d12_1 = w2 * e12;
+        if d12_1 <= 0. {
+            return self.keep(&[(1, 1.)]);
+        }
+
+        let inv = 1. / (d12_1 + d12_2);
+        self.keep(&[(0, d12_1 * inv), (1, d12_2 * inv)]);
+    }
+
+    fn solve_triangle(&mut self) {
+        let w1 = self.vertices[0].vertex.to_vec();
+        let w2 = self.vertices[1].vertex.to_vec();
+        let w3 = self.vertices[2].vertex.to_vec();
+
+        let e12 = w2 - w1;
+        let d12_1 = w2 * e12;
+        let d12_2 = -(w1 * e12);
+
+        let e13 = w3 - w1;
+        let d13_1 = w3 * e13;
+        let d13_2 = -(w1 * e13);
+
+        let e23 = w3 - w2;
+        let d23_1 = w3 * e23;
+        let d23_2 = -(w2 * e23);
+
+        let n123 = e12.cross(e13);
+        let d123_1 = n123 * w2.cross(w3);
+        let d123_2 = n123 * w3.cross(w1);
+        let d123_3 = n123 * w1.cross(w2);
+
+        if d12_2 <= 0. && d13_2 <= 0. {
+            self.keep(&[(0, 1.)]);
+        } else if d12_1 > 0. && d12_2 > 0. && d123_3 <= 0. {
+            let inv = 1. / (d12_1 + d12_2);
+            self.keep(&[(0, d12_1 * inv), (1, d12_2 * inv)]);
+        } else if d13_1 > 0. && d13_2 > 0. && d123_2 <= 0. {
+            let inv = 1. / (d13_1 + d13_2);
+            self.keep(&[(0, d13_1 * inv), (2, d13_2 * inv)]);
+        } else if d12_1 <= 0. && d23_2 <= 0. {
+            self.keep(&[(1, 1.)]);
+        } else if d13_1 <= 0. && d23_1 <= 0. {
+            self.keep(&[(2, 1.)]);
+        } else if d23_1 > 0. && d23_2 > 0. && d123_1 <= 0. {
+            let inv = 1. / (d23_1 + d23_2);
+            self.keep(&[(1, d23_1 * inv), (2, d23_2 * inv)]);
         } else {
-            simplex[0] = support;
+            // The origin is inside the triangle.
+            let inv = 1. / (d123_1 + d123_2 + d123_3);
+            self.keep(&[(0, d123_1 * inv), (1, d123_2 * inv), (2, d123_3 * inv)]);
+        }
+    }
+
+    fn closest_point(&self) -> Vec2D {
+        self.vertices
+            .iter()
+            .fold(Vec2D::zero(), |acc, v| acc + v.vertex.to_vec() * v.weight)
+    }
+
+    /// The vertices, for EPA.
+    fn to_simplex(&self) -> Simplex {
+        Simplex {
+            points: self.vertices.iter().map(|v| v.vertex).collect(),
         }
     }
 
-    // clg!("Runned out of iterations.");
-    None
+    /// The points of each shape whose difference is the closest point.
+    fn witness_points(&self) -> (Vec2D, Vec2D) {
+        self.vertices
+            .iter()
+            .fold((Vec2D::zero(), Vec2D::zero()), |(point_a, point_b), v| {
+                (
+                    point_a + v.vertex.a * v.weight,
+                    point_b + v.vertex.b * v.weight,
+                )
+            })
+    }
+}
+
+const SHAPE_CAST_ITERATIONS: u32 = 50;
+
+/// Moves `a` along `translation` (without rotating it) and finds the first time it touches `b`.
+/// The returned fraction is the part of `translation` travelled before the impact, the point
+/// is on the surface of `b` and the normal points out of `b`.
+/// Bodies overlapping from the start don't hit each other.
+///
+/// From: http://dtecta.com/papers/jgt04raycast.pdf
+/// And: https://github.com/erincatto/box2d/blob/main/src/collision/b2_distance.cpp (b2ShapeCast)
+pub fn shape_cast(
+    a: &dyn SupportMap,
+    b: &dyn SupportMap,
+    translation: Vec2D,
+) -> Result<Option<RayHit>, CollisionError> {
+    if translation.squared_norm() == 0. {
+        return Ok(None);
+    }
+
+    // Target distance between the shapes at the impact, and the precision on it.
+    let sigma = TOLERANCE;
+    let tolerance = 0.5 * TOLERANCE;
+
+    let mut fraction = 0.;
+    let mut normal = Vec2D::zero();
+    let mut simplex = WeightedSimplex::default();
+
+    // The simplex lives in the Minkowski difference of b and of a moved by `fraction`.
+    let mut v = CSOVertex::get(b, a, -translation)?.to_vec();
+    let mut iterations = 0;
+
+    while iterations < SHAPE_CAST_ITERATIONS && v.norm() - sigma > tolerance {
+        let support = CSOVertex::get(b, a, -v)?;
+        let p = support.to_vec();
+
+        // -v is the normal of the plane through p bounding the Minkowski difference.
+        let v_unit = v.normalize();
+        let vp = v_unit * p;
+        let vr = v_unit * translation;
+
+        if vp - sigma > fraction * vr {
+            if vr <= 0. {
+                // Moving away from the plane: never hits.
+                return Ok(None);
+            }
+
+            fraction = (vp - sigma) / vr;
+
+            if fraction > 1. {
+                return Ok(None);
+            }
+
+            normal = -v_unit;
+            simplex.clear();
+        }
+
+        // The points of a are shifted to where a is at `fraction`, but not the support point p
+        // as the plane equation is computed in the unshifted space.
+        simplex.add(CSOVertex::new(
+            support.a,
+            support.b + translation * fraction,
+        ));
+        simplex.solve();
+
+        if simplex.len() == 3 {
+            // The origin is inside the Minkowski difference: overlapping.
+            return Ok(None);
+        }
+
+        v = simplex.closest_point();
+        iterations += 1;
+    }
+
+    if iterations == 0 {
+        // Initially touching
+        return Ok(None);
+    }
+
+    let (point_b, _) = simplex.witness_points();
+
+    if v.squared_norm() > 0. {
+        normal = -v.normalize();
+    }
+
+    Ok(Some(RayHit {
+        fraction,
+        point: point_b,
+        normal,
+    }))
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         collision::{
-            algorithms::gjk::{gjk_collision, CSOVertex, Simplex},
-            collider::Collider::{CircleCollider, PolygonCollider},
+            algorithms::gjk::{gjk_collision, shape_cast, CSOVertex, Simplex},
+            collider::Collider::{self, CircleCollider, PolygonCollider},
+            error::CollisionError,
         },
         linalg::Vec2D,
         rigidbody2d::RigidBody2D,
@@ -384,33 +643,35 @@ mod tests {
             Vec2D::new(5., 7.),
         ];
 
-        let a = RigidBody2D::from_polygon(&a_vertices, 1.);
+        let a = RigidBody2D::from_polygon(&a_vertices, 1.).unwrap();
 
         // assert_eq!(a.position, Vec2D::new(5.5, 8.5));
 
-        let b = RigidBody2D::from_polygon(&b_vertices, 1.);
+        let b = RigidBody2D::from_polygon(&b_vertices, 1.).unwrap();
 
         // assert_eq!(b.position, Vec2D::new(9., 5.));
 
         assert_eq!(
-            CSOVertex::get(&a, &b, Vec2D::new(1., 0.)).to_vec(),
+            CSOVertex::get(&a, &b, Vec2D::new(1., 0.)).unwrap().to_vec(),
             Vec2D::new(4., 2.)
         );
         assert_eq!(
-            CSOVertex::get(&a, &b, Vec2D::new(-1., 0.)).to_vec(),
+            CSOVertex::get(&a, &b, Vec2D::new(-1., 0.))
+                .unwrap()
+                .to_vec(),
             Vec2D::new(-8., -2.)
         );
         assert_eq!(
-            CSOVertex::get(&a, &b, Vec2D::new(0., 1.)).to_vec(),
+            CSOVertex::get(&a, &b, Vec2D::new(0., 1.)).unwrap().to_vec(),
             Vec2D::new(-6., 9.)
         );
 
         let mut expected_result = Simplex::new();
-        expected_result.add(CSOVertex::get(&a, &b, Vec2D::new(1., -1.)));
-        expected_result.add(CSOVertex::get(&a, &b, Vec2D::new(-0.573, -0.819)));
-        expected_result.add(CSOVertex::get(&a, &b, Vec2D::new(0.316, -0.948)));
+        expected_result.add(CSOVertex::get(&a, &b, Vec2D::new(1., -1.)).unwrap());
+        expected_result.add(CSOVertex::get(&a, &b, Vec2D::new(-0.573, -0.819)).unwrap());
+        expected_result.add(CSOVertex::get(&a, &b, Vec2D::new(0.316, -0.948)).unwrap());
 
-        assert_eq!(gjk_collision(&a, &b), Some(expected_result))
+        assert_eq!(gjk_collision(&a, &b), Ok(Some(expected_result)))
     }
 
     #[test]
@@ -441,6 +702,7 @@ mod tests {
                     Vec2D { x: 100.0, y: 50.0 },
                     Vec2D { x: 100.0, y: -50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
@@ -449,7 +711,7 @@ mod tests {
 
         assert_eq!(
             gjk_collision(&a, &b),
-            Some(Simplex {
+            Ok(Some(Simplex {
                 points: vec![
                     CSOVertex {
                         a: Vec2D {
@@ -473,7 +735,56 @@ mod tests {
                         b: Vec2D { x: 322.0, y: 311.0 }
                     }
                 ]
-            })
+            }))
         )
     }
+
+    #[test]
+    fn example_three() {
+        let a = RigidBody2D::new(Vec2D::new(f64::NAN, 0.), Collider::circle(1.), 1.);
+        let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
+
+        assert_eq!(
+            gjk_collision(&a, &b),
+            Err(CollisionError::InvalidSupportPoint)
+        );
+        assert_eq!(
+            shape_cast(&b, &a, Vec2D::new(1., 0.)),
+            Err(CollisionError::InvalidSupportPoint)
+        );
+    }
+
+    #[test]
+    fn shape_cast_one() {
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(5., 0.), Collider::rectangle(2., 2.), 1.);
+
+        let hit = shape_cast(&a, &b, Vec2D::new(10., 0.)).unwrap().unwrap();
+
+        assert!((hit.fraction - 0.3).abs() < 1e-3);
+        assert!((hit.point.x - 4.).abs() < 1e-3);
+        assert!(hit.point.y.abs() < 0.05);
+        assert!((hit.normal - Vec2D::new(-1., 0.)).near_zero());
+
+        // Going away
+        assert_eq!(shape_cast(&a, &b, Vec2D::new(-10., 0.)), Ok(None));
+        // Too short
+        assert_eq!(shape_cast(&a, &b, Vec2D::new(2., 0.)), Ok(None));
+    }
+
+    #[test]
+    fn shape_cast_two() {
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
+        let mut b = RigidBody2D::new(Vec2D::new(0.5, 5.), Collider::rectangle(2., 2.), 1.);
+
+        let hit = shape_cast(&a, &b, Vec2D::new(0., 10.)).unwrap().unwrap();
+
+        assert!((hit.fraction - 0.3).abs() < 1e-3);
+        assert!((hit.point.y - 4.).abs() < 1e-3);
+        assert!((hit.normal - Vec2D::new(0., -1.)).near_zero());
+
+        // Already overlapping
+        b.position = Vec2D::new(0.5, 1.5);
+        assert_eq!(shape_cast(&a, &b, Vec2D::new(0., 10.)), Ok(None));
+    }
 }
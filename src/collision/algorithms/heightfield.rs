@@ -0,0 +1,79 @@
+use crate::{collision::aabb::Aabb, linalg::Vec2D};
+
+/// The sample `i` of a heightfield in its local space.
+pub fn sample(heights: &[f64], spacing: f64, i: usize) -> Vec2D {
+    Vec2D::new(i as f64 * spacing, heights[i])
+}
+
+/// The index of the cell, going from sample `i` to sample `i + 1`, under the abscissa `x`.
+/// Abscissas beyond the ends fall in the first and last cells.
+pub fn cell_at(heights: &[f64], spacing: f64, x: f64) -> usize {
+    ((x / spacing).floor().max(0.) as usize).min(heights.len() - 2)
+}
+
+/// The cells whose bounding box overlaps `aabb`, given in the local space of the heightfield.
+/// Only the cells between the sides of the box are looked at.
+pub fn overlapped_cells(heights: &[f64], spacing: f64, aabb: &Aabb) -> Vec<usize> {
+    let width = spacing * (heights.len() - 1) as f64;
+
+    if aabb.max.x < 0. || aabb.min.x > width {
+        return vec![];
+    }
+
+    let first = cell_at(heights, spacing, aabb.min.x);
+    let last = cell_at(heights, spacing, aabb.max.x);
+
+    (first..=last)
+        .filter(|i| {
+            let (low, high) = (
+                heights[*i].min(heights[i + 1]),
+                heights[*i].max(heights[i + 1]),
+            );
+            low <= aabb.max.y && aabb.min.y <= high
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            aabb::Aabb, algorithms::heightfield::overlapped_cells, collider::Collider, ray::Ray,
+        },
+        linalg::Vec2D,
+    };
+
+    #[test]
+    fn example_one() {
+        let heights = [0., 1., 3., 1., 0., 0.];
+
+        // Across the peak, and over the flat end
+        let aabb = Aabb::new(Vec2D::new(1.5, 1.5), Vec2D::new(4.5, 2.));
+        assert_eq!(overlapped_cells(&heights, 1., &aabb), vec![1, 2]);
+
+        let aabb = Aabb::new(Vec2D::new(-3., -1.), Vec2D::new(-1., 5.));
+        assert!(overlapped_cells(&heights, 1., &aabb).is_empty());
+
+        // The ray marches from the right and stops on the first slope it meets.
+        let terrain = Collider::heightfield(&heights, 1.).unwrap();
+        let hit = terrain
+            .raycast(&Ray::new(Vec2D::new(6., 2.), Vec2D::new(-1., 0.), 10.))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(hit.point, Vec2D::new(2.5, 2.));
+        assert!((hit.normal - Vec2D::new(2., 1.).normalize()).near_zero());
+
+        // Going down, the ray meets the flat part.
+        let hit = terrain
+            .raycast(&Ray::between(Vec2D::new(4.5, 5.), Vec2D::new(4.5, -5.)))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(hit.point, Vec2D::new(4.5, 0.));
+        assert!(terrain
+            .raycast(&Ray::between(Vec2D::new(-1., 5.), Vec2D::new(8., 5.)))
+            .unwrap()
+            .is_none());
+    }
+}
@@ -0,0 +1,131 @@
+// Andrew's monotone chain
+// From: https://en.wikibooks.org/wiki/Algorithm_Implementation/Geometry/Convex_hull/Monotone_chain
+
+use crate::linalg::{Vec2D, TOLERANCE};
+
+/// Whether `a` is to the right of the line going from `o` to `b`, or closer to it than
+/// `TOLERANCE`: it's then not a vertex of the hull.
+fn is_not_left(o: Vec2D, a: Vec2D, b: Vec2D) -> bool {
+    (a - o).cross(b - o) <= TOLERANCE * (b - o).norm()
+}
+
+/// The vertices of the convex hull of `points`, counter-clockwise (with the y axis pointing up),
+/// starting from the lowest leftmost one. Duplicated and collinear points are removed, up to
+/// `TOLERANCE`. Less than three vertices are returned when the points are all on a line.
+pub fn convex_hull(points: &[Vec2D]) -> Vec<Vec2D> {
+    let mut points = points.to_vec();
+    points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    points.dedup_by(|a, b| (*a - *b).near_zero());
+
+    if points.len() < 3 {
+        return points;
+    }
+
+    let mut hull: Vec<Vec2D> = vec![];
+
+    // Lower part, from left to right
+    for p in &points {
+        while hull.len() >= 2 && is_not_left(hull[hull.len() - 2], hull[hull.len() - 1], *p) {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+
+    // Upper part, from right to left
+    let lower_len = hull.len() + 1;
+    for p in points.iter().rev().skip(1) {
+        while hull.len() >= lower_len && is_not_left(hull[hull.len() - 2], hull[hull.len() - 1], *p)
+        {
+            hull.pop();
+        }
+        hull.push(*p);
+    }
+
+    // The first point is back at the end.
+    hull.pop();
+
+    // Points closer than the tolerance, but not next to each other once sorted
+    hull.dedup_by(|a, b| (*a - *b).near_zero());
+    while hull.len() > 1 && (hull[0] - hull[hull.len() - 1]).near_zero() {
+        hull.pop();
+    }
+
+    hull
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            algorithms::hull::convex_hull,
+            collider::{Collider, PolygonError},
+        },
+        linalg::Vec2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // A grid of points: only the corners are kept.
+        let mut points = vec![];
+        for i in 0..4 {
+            for j in 0..3 {
+                points.push(Vec2D::new(i as f64, j as f64));
+            }
+        }
+        // Duplicated, and almost on an edge
+        points.push(Vec2D::new(3., 2.));
+        points.push(Vec2D::new(1.5, -0.0005));
+
+        assert_eq!(
+            convex_hull(&points),
+            vec![
+                Vec2D::new(0., 0.),
+                Vec2D::new(3., 0.),
+                Vec2D::new(3., 2.),
+                Vec2D::new(0., 2.),
+            ]
+        );
+    }
+
+    #[test]
+    fn example_two() {
+        let points = [
+            Vec2D::new(0., 3.),
+            Vec2D::new(-2., 1.),
+            Vec2D::new(0., 0.),
+            Vec2D::new(0., 1.),
+            Vec2D::new(2., 1.),
+            Vec2D::new(1., 2.),
+        ];
+
+        let hull = Collider::convex_hull(&points).unwrap();
+
+        match &hull {
+            Collider::PolygonCollider { vertices, .. } => assert_eq!(
+                vertices,
+                &vec![
+                    Vec2D::new(-2., 1.),
+                    Vec2D::new(0., 0.),
+                    Vec2D::new(2., 1.),
+                    Vec2D::new(0., 3.),
+                ]
+            ),
+            _ => panic!("A hull is a polygon."),
+        }
+
+        assert_eq!(
+            Collider::convex_hull(&[Vec2D::new(0., 0.), Vec2D::new(1., 1.)]).unwrap_err(),
+            PolygonError::TooFewVertices
+        );
+        assert_eq!(
+            Collider::convex_hull(&[
+                Vec2D::new(0., 0.),
+                Vec2D::new(1., 1.),
+                Vec2D::new(2., 2.0001),
+                Vec2D::new(1., 1.),
+            ])
+            .unwrap_err(),
+            PolygonError::ZeroArea
+        );
+    }
+}
@@ -1,5 +1,12 @@
 use crate::{
-    collision::collider::Collider::{CircleCollider, PolygonCollider},
+    collision::{
+        algorithms::segment::{capsule_segment, closest_point_on_segment},
+        collider::Collider::{
+            CapsuleCollider, ChainCollider, CircleCollider, CompoundCollider, ConvexCollider,
+            EllipseCollider, HeightfieldCollider, PolygonCollider, SegmentCollider,
+        },
+        error::CollisionError,
+    },
     linalg::Vec2D,
     rigidbody2d::RigidBody2D,
 };
@@ -11,8 +18,9 @@ struct ProjectedShape {
 }
 
 impl ProjectedShape {
-    fn new(axis: Vec2D, shape: &RigidBody2D) -> Self {
-        match &shape.collider {
+    /// Fails with `UnsupportedPair` for shapes that aren't convex.
+    fn new(axis: Vec2D, shape: &RigidBody2D) -> Result<Self, CollisionError> {
+        let projection = match &shape.collider {
             CircleCollider { radius } => {
                 let mid = axis * shape.position;
                 Self {
@@ -20,7 +28,7 @@ impl ProjectedShape {
                     mini: mid - radius,
                 }
             }
-            PolygonCollider { vertices } => {
+            PolygonCollider { vertices, radius } => {
                 let polygon: Vec<Vec2D> = vertices.iter().map(|v| shape.to_global(*v)).collect();
 
                 let mut min = polygon[0] * axis;
@@ -38,11 +46,43 @@ impl ProjectedShape {
                 }
 
                 Self {
-                    mini: min,
-                    maxi: max,
+                    mini: min - radius,
+                    maxi: max + radius,
                 }
             }
-        }
+            CapsuleCollider { .. } => {
+                let (p1, p2, radius) = capsule_segment(shape)?;
+                let (proj_1, proj_2) = (axis * p1, axis * p2);
+
+                Self {
+                    mini: proj_1.min(proj_2) - radius,
+                    maxi: proj_1.max(proj_2) + radius,
+                }
+            }
+            SegmentCollider { start, end } => {
+                let (proj_1, proj_2) =
+                    (axis * shape.to_global(*start), axis * shape.to_global(*end));
+
+                Self {
+                    mini: proj_1.min(proj_2),
+                    maxi: proj_1.max(proj_2),
+                }
+            }
+            EllipseCollider { .. } | ConvexCollider { .. } => {
+                let local_axis = shape.to_local(axis);
+
+                Self {
+                    mini: axis * shape.to_global(shape.collider.support(-local_axis)),
+                    maxi: axis * shape.to_global(shape.collider.support(local_axis)),
+                }
+            }
+            // Use their segments, or their children, instead.
+            ChainCollider { .. } | HeightfieldCollider { .. } | CompoundCollider { .. } => {
+                return Err(CollisionError::UnsupportedPair)
+            }
+        };
+
+        Ok(projection)
     }
 
     fn overlap(&self, other: &Self) -> bool {
@@ -73,10 +113,14 @@ impl ProjectedShape {
     }
 }
 
-fn get_foci(body: &RigidBody2D) -> Vec<Vec2D> {
+fn get_foci(body: &RigidBody2D) -> Result<Vec<Vec2D>, CollisionError> {
     match &body.collider {
-        CircleCollider { .. } => vec![body.position],
-        _ => vec![],
+        CircleCollider { .. } => Ok(vec![body.position]),
+        CapsuleCollider { .. } => {
+            let (p1, p2, _) = capsule_segment(body)?;
+            Ok(vec![p1, p2])
+        }
+        _ => Ok(vec![]),
     }
 }
 
@@ -95,10 +139,24 @@ fn get_normals(points: &Vec<Vec2D>) -> Vec<Vec2D> {
     normals
 }
 
-fn get_axes(a: &RigidBody2D, foci: &Vec<Vec2D>) -> Vec<Vec2D> {
-    match &a.collider {
-        CircleCollider { .. } => vec![],
-        PolygonCollider { vertices } => {
+/// The normal of the segment `[p1, p2]`, and the directions from it to each focus.
+fn get_segment_axes(p1: Vec2D, p2: Vec2D, foci: &Vec<Vec2D>) -> Vec<Vec2D> {
+    let mut res = vec![(p2 - p1).right().normalize()];
+
+    for focus in foci {
+        let (closest, _) = closest_point_on_segment(*focus, p1, p2);
+        res.push((*focus - closest).normalize());
+    }
+
+    res
+}
+
+/// Fails with `UnsupportedPair` for shapes that aren't convex.
+fn get_axes(a: &RigidBody2D, foci: &Vec<Vec2D>) -> Result<Vec<Vec2D>, CollisionError> {
+    let axes = match &a.collider {
+        // Curved shapes have too many axes to test: see `gjk_v_shape`.
+        CircleCollider { .. } | EllipseCollider { .. } | ConvexCollider { .. } => vec![],
+        PolygonCollider { vertices, .. } => {
             let polygon: Vec<Vec2D> = vertices.iter().map(|v| a.to_global(*v)).collect();
 
             println!("Getting the axes of the polygon: {:#?}", polygon);
@@ -127,19 +185,34 @@ fn get_axes(a: &RigidBody2D, foci: &Vec<Vec2D>) -> Vec<Vec2D> {
 
             res
         }
-    }
+        CapsuleCollider { .. } => {
+            let (p1, p2, _) = capsule_segment(a)?;
+            get_segment_axes(p1, p2, foci)
+        }
+        SegmentCollider { start, end } => {
+            get_segment_axes(a.to_global(*start), a.to_global(*end), foci)
+        }
+        // Use their segments, or their children, instead.
+        ChainCollider { .. } | HeightfieldCollider { .. } | CompoundCollider { .. } => {
+            return Err(CollisionError::UnsupportedPair)
+        }
+    };
+
+    Ok(axes)
 }
 
 /// From: https://dyn4j.org/2010/01/sat/
-pub fn sat(a: &RigidBody2D, b: &RigidBody2D) -> Option<Vec2D> {
+/// Fails with `UnsupportedPair` when either shape isn't convex: chains, heightfields and
+/// compounds.
+pub fn sat(a: &RigidBody2D, b: &RigidBody2D) -> Result<Option<Vec2D>, CollisionError> {
     let mut overlap = std::f64::INFINITY;
     let mut smallest = None;
 
-    let foci_a = get_foci(a);
-    let foci_b = get_foci(b);
+    let foci_a = get_foci(a)?;
+    let foci_b = get_foci(b)?;
 
-    let mut axes_a = get_axes(a, &foci_b);
-    let mut axes_b = get_axes(b, &foci_a);
+    let mut axes_a = get_axes(a, &foci_b)?;
+    let mut axes_b = get_axes(b, &foci_a)?;
 
     axes_a.append(&mut axes_b);
 
@@ -152,14 +225,14 @@ pub fn sat(a: &RigidBody2D, b: &RigidBody2D) -> Option<Vec2D> {
 
         println!("With axis: {axis} =====");
 
-        let p_a = ProjectedShape::new(axis, a);
+        let p_a = ProjectedShape::new(axis, a)?;
 
-        let p_b = ProjectedShape::new(axis, b);
+        let p_b = ProjectedShape::new(axis, b)?;
 
         println!("Got projections: p_a: {:#?} p_b: {:#?}", p_a, p_b);
 
         if !p_a.overlap(&p_b) {
-            return None;
+            return Ok(None);
         } else {
             let mut o = p_a.get_overlap(&p_b);
 
@@ -190,7 +263,7 @@ pub fn sat(a: &RigidBody2D, b: &RigidBody2D) -> Option<Vec2D> {
         }
     }
 
-    smallest
+    Ok(smallest)
 }
 
 #[cfg(test)]
@@ -198,7 +271,11 @@ mod test {
     use crate::{
         collision::{
             algorithms::sat::sat,
-            collider::Collider::{self, CircleCollider, PolygonCollider},
+            collider::{
+                Collider::{self, CircleCollider, PolygonCollider},
+                CompoundChild,
+            },
+            error::CollisionError,
         },
         linalg::Vec2D,
         rigidbody2d::RigidBody2D,
@@ -218,7 +295,7 @@ mod test {
         // let mut axes_b = get_axes(&b, &foci_a);
         // println!("axes_b: {:#?}", axes_b);
 
-        assert_eq!(sat(&a, &b), Some(Vec2D::new(0., 1.)));
+        assert_eq!(sat(&a, &b), Ok(Some(Vec2D::new(0., 1.))));
         // assert!(false);
     }
 
@@ -227,7 +304,7 @@ mod test {
         let a = RigidBody2D::new(Vec2D::new(2.5, 2.), Collider::rectangle(3., 2.), 1.);
         let b = RigidBody2D::new(Vec2D { x: 5., y: 4. }, Collider::circle(1.), 1.);
 
-        assert_eq!(sat(&a, &b), None);
+        assert_eq!(sat(&a, &b), Ok(None));
     }
 
     #[test]
@@ -258,12 +335,36 @@ mod test {
                     Vec2D { x: 100.0, y: 50.0 },
                     Vec2D { x: -100.0, y: 50.0 },
                 ],
+                radius: 0.,
             },
             mass: 1.0,
             force_generators: vec![],
             inertia: 1.,
         };
 
-        assert_eq!(sat(&a, &b), None);
+        assert_eq!(sat(&a, &b), Ok(None));
+    }
+
+    #[test]
+    fn example_four() {
+        // Neither chains nor compounds are convex.
+        let chain = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::chain(&[Vec2D::new(-1., 1.), Vec2D::zero(), Vec2D::new(1., 1.)]).unwrap(),
+            0.,
+        );
+        let l = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::compound(vec![
+                CompoundChild::new(Collider::rectangle(2., 1.), Vec2D::zero(), 0.),
+                CompoundChild::new(Collider::rectangle(1., 2.), Vec2D::new(-0.5, 1.), 0.),
+            ])
+            .unwrap(),
+            1.,
+        );
+        let circle = RigidBody2D::new(Vec2D::new(0., 0.5), Collider::circle(1.), 1.);
+
+        assert_eq!(sat(&chain, &circle), Err(CollisionError::UnsupportedPair));
+        assert_eq!(sat(&circle, &l), Err(CollisionError::UnsupportedPair));
     }
 }
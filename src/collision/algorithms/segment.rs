@@ -0,0 +1,329 @@
+use crate::{
+    collision::{
+        aabb::Aabb,
+        algorithms::heightfield::{overlapped_cells, sample},
+        collider::Collider::{
+            CapsuleCollider, ChainCollider, HeightfieldCollider, SegmentCollider,
+        },
+        error::CollisionError,
+    },
+    linalg::Vec2D,
+    rigidbody2d::RigidBody2D,
+};
+
+/// The ends of the inner segment of a capsule in world space, and its radius.
+/// Fails with `UnsupportedPair` for any other shape.
+pub fn capsule_segment(body: &RigidBody2D) -> Result<(Vec2D, Vec2D, f64), CollisionError> {
+    match body.collider {
+        CapsuleCollider {
+            half_length,
+            radius,
+        } => Ok((
+            body.to_global(Vec2D::new(-half_length, 0.)),
+            body.to_global(Vec2D::new(half_length, 0.)),
+            radius,
+        )),
+        _ => Err(CollisionError::UnsupportedPair),
+    }
+}
+
+/// A segment of a chain in world space, along with the vertices before and after it, if any.
+/// Those ghost vertices tell which contacts belong to the neighbouring segments.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChainSegment {
+    pub ghost_1: Option<Vec2D>,
+    pub start: Vec2D,
+    pub end: Vec2D,
+    pub ghost_2: Option<Vec2D>,
+}
+
+/// The segments of the cells of a heightfield in world space, with the neighbouring samples as
+/// ghost vertices.
+fn cell_segments(
+    body: &RigidBody2D,
+    heights: &[f64],
+    spacing: f64,
+    cells: &[usize],
+) -> Vec<ChainSegment> {
+    let vertex = |i: isize| {
+        if i < 0 || i >= heights.len() as isize {
+            None
+        } else {
+            Some(body.to_global(sample(heights, spacing, i as usize)))
+        }
+    };
+
+    cells
+        .iter()
+        .map(|cell| {
+            let i = *cell as isize;
+            ChainSegment {
+                ghost_1: vertex(i - 1),
+                start: vertex(i).unwrap(),
+                end: vertex(i + 1).unwrap(),
+                ghost_2: vertex(i + 2),
+            }
+        })
+        .collect()
+}
+
+/// The segments of the cells of a heightfield overlapping `aabb`, all in world space. Fails with
+/// `UnsupportedPair` for any other shape.
+pub fn heightfield_segments(
+    body: &RigidBody2D,
+    aabb: &Aabb,
+) -> Result<Vec<ChainSegment>, CollisionError> {
+    match &body.collider {
+        HeightfieldCollider { heights, spacing } => {
+            let corners = [
+                aabb.min,
+                Vec2D::new(aabb.max.x, aabb.min.y),
+                aabb.max,
+                Vec2D::new(aabb.min.x, aabb.max.y),
+            ];
+            let corners: Vec<Vec2D> = corners
+                .iter()
+                .map(|c| body.to_local(*c - body.position))
+                .collect();
+
+            let cells = overlapped_cells(heights, *spacing, &Aabb::from_points(&corners));
+            Ok(cell_segments(body, heights, *spacing, &cells))
+        }
+        _ => Err(CollisionError::UnsupportedPair),
+    }
+}
+
+/// The segments of a segment, chain or heightfield collider in world space. Fails with
+/// `UnsupportedPair` for any other shape.
+pub fn chain_segments(body: &RigidBody2D) -> Result<Vec<ChainSegment>, CollisionError> {
+    match &body.collider {
+        SegmentCollider { start, end } => Ok(vec![ChainSegment {
+            ghost_1: None,
+            start: body.to_global(*start),
+            end: body.to_global(*end),
+            ghost_2: None,
+        }]),
+        ChainCollider { vertices, looped } => {
+            let vertices: Vec<Vec2D> = vertices.iter().map(|v| body.to_global(*v)).collect();
+            let n = vertices.len();
+
+            let vertex = |i: isize| {
+                if *looped {
+                    Some(vertices[i.rem_euclid(n as isize) as usize])
+                } else if i < 0 || i >= n as isize {
+                    None
+                } else {
+                    Some(vertices[i as usize])
+                }
+            };
+
+            let count = if *looped { n } else { n - 1 };
+
+            Ok((0..count as isize)
+                .map(|i| ChainSegment {
+                    ghost_1: vertex(i - 1),
+                    start: vertex(i).unwrap(),
+                    end: vertex(i + 1).unwrap(),
+                    ghost_2: vertex(i + 2),
+                })
+                .collect())
+        }
+        HeightfieldCollider { heights, spacing } => {
+            let cells: Vec<usize> = (0..heights.len() - 1).collect();
+            Ok(cell_segments(body, heights, *spacing, &cells))
+        }
+        _ => Err(CollisionError::UnsupportedPair),
+    }
+}
+
+/// Closest point to `point` on the segment `[a, b]`, with its parameter in `[0, 1]`.
+pub fn closest_point_on_segment(point: Vec2D, a: Vec2D, b: Vec2D) -> (Vec2D, f64) {
+    let ab = b - a;
+    let l_sq = ab.squared_norm();
+
+    if l_sq == 0. {
+        return (a, 0.);
+    }
+
+    let t = ((point - a) * ab / l_sq).clamp(0., 1.);
+
+    (a + ab * t, t)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SegmentDistance {
+    pub point_1: Vec2D,
+    pub point_2: Vec2D,
+    /// Parameter of `point_1` on the first segment, exactly `0.` or `1.` on its ends.
+    pub fraction_1: f64,
+    /// Parameter of `point_2` on the second segment, exactly `0.` or `1.` on its ends.
+    pub fraction_2: f64,
+    pub distance_squared: f64,
+}
+
+/// Closest points between the segments `[p1, q1]` and `[p2, q2]`.
+///
+/// From: Real-Time Collision Detection, Christer Ericson, 5.1.9
+pub fn segment_distance(p1: Vec2D, q1: Vec2D, p2: Vec2D, q2: Vec2D) -> SegmentDistance {
+    let d1 = q1 - p1;
+    let d2 = q2 - p2;
+    let r = p1 - p2;
+    let a = d1.squared_norm();
+    let e = d2.squared_norm();
+    let f = d2 * r;
+
+    let (s, t) = if a == 0. && e == 0. {
+        (0., 0.)
+    } else if a == 0. {
+        (0., (f / e).clamp(0., 1.))
+    } else {
+        let c = d1 * r;
+
+        if e == 0. {
+            ((-c / a).clamp(0., 1.), 0.)
+        } else {
+            let b = d1 * d2;
+            let denom = a * e - b * b;
+
+            // Closest point on the first infinite line, unless parallel.
+            let mut s = if denom != 0. {
+                ((b * f - c * e) / denom).clamp(0., 1.)
+            } else {
+                0.
+            };
+
+            let mut t = (b * s + f) / e;
+
+            if t < 0. {
+                t = 0.;
+                s = (-c / a).clamp(0., 1.);
+            } else if t > 1. {
+                t = 1.;
+                s = ((b - c) / a).clamp(0., 1.);
+            }
+
+            (s, t)
+        }
+    };
+
+    let point_1 = p1 + d1 * s;
+    let point_2 = p2 + d2 * t;
+
+    SegmentDistance {
+        point_1,
+        point_2,
+        fraction_1: s,
+        fraction_2: t,
+        distance_squared: (point_2 - point_1).squared_norm(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            algorithms::segment::{
+                capsule_segment, chain_segments, closest_point_on_segment, segment_distance,
+                ChainSegment,
+            },
+            collider::Collider,
+            error::CollisionError,
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        let a = Vec2D::new(-1., 0.);
+        let b = Vec2D::new(1., 0.);
+
+        assert_eq!(
+            closest_point_on_segment(Vec2D::new(0.5, 3.), a, b),
+            (Vec2D::new(0.5, 0.), 0.75)
+        );
+        assert_eq!(closest_point_on_segment(Vec2D::new(-4., 3.), a, b), (a, 0.));
+        assert_eq!(closest_point_on_segment(Vec2D::new(4., -3.), a, b), (b, 1.));
+    }
+
+    #[test]
+    fn example_two() {
+        // Crossing
+        let res = segment_distance(
+            Vec2D::new(-1., 0.),
+            Vec2D::new(1., 0.),
+            Vec2D::new(0., -1.),
+            Vec2D::new(0., 1.),
+        );
+        assert_eq!(res.distance_squared, 0.);
+        assert_eq!((res.fraction_1, res.fraction_2), (0.5, 0.5));
+
+        // End to end
+        let res = segment_distance(
+            Vec2D::new(0., 0.),
+            Vec2D::new(1., 0.),
+            Vec2D::new(2., 1.),
+            Vec2D::new(3., 5.),
+        );
+        assert_eq!((res.fraction_1, res.fraction_2), (1., 0.));
+        assert_eq!(res.distance_squared, 2.);
+
+        // End to interior
+        let res = segment_distance(
+            Vec2D::new(0., 2.),
+            Vec2D::new(0., 1.),
+            Vec2D::new(-1., 0.),
+            Vec2D::new(1., 0.),
+        );
+        assert_eq!((res.fraction_1, res.fraction_2), (1., 0.5));
+        assert_eq!(res.point_2, Vec2D::new(0., 0.));
+    }
+
+    #[test]
+    fn example_three() {
+        let (a, b, c) = (Vec2D::new(0., 0.), Vec2D::new(1., 0.), Vec2D::new(1., 1.));
+
+        let open = RigidBody2D::new(Vec2D::new(1., 0.), Collider::chain(&[a, b, c]).unwrap(), 0.);
+        assert_eq!(
+            chain_segments(&open),
+            Ok(vec![
+                ChainSegment {
+                    ghost_1: None,
+                    start: Vec2D::new(1., 0.),
+                    end: Vec2D::new(2., 0.),
+                    ghost_2: Some(Vec2D::new(2., 1.)),
+                },
+                ChainSegment {
+                    ghost_1: Some(Vec2D::new(1., 0.)),
+                    start: Vec2D::new(2., 0.),
+                    end: Vec2D::new(2., 1.),
+                    ghost_2: None,
+                },
+            ])
+        );
+
+        let looped = RigidBody2D::new(Vec2D::zero(), Collider::chain_loop(&[a, b, c]).unwrap(), 0.);
+        let segments = chain_segments(&looped).unwrap();
+        assert_eq!(segments.len(), 3);
+        assert_eq!(
+            segments[2],
+            ChainSegment {
+                ghost_1: Some(b),
+                start: c,
+                end: a,
+                ghost_2: Some(b),
+            }
+        );
+
+        // Not a chain
+        let circle = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 1.);
+        assert_eq!(
+            chain_segments(&circle),
+            Err(CollisionError::UnsupportedPair)
+        );
+        assert_eq!(
+            capsule_segment(&circle),
+            Err(CollisionError::UnsupportedPair)
+        );
+    }
+}
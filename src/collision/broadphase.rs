@@ -1,5 +1,12 @@
-use crate::linalg::Vec2D;
+pub mod brute_force;
+pub mod spatial_hash;
 
-trait Broadphase {
-    fn get_contact_constraint(bodies: Vec<Vec2D>) {}
+use crate::rigidbody2d::RigidBody2D;
+
+/// A broadphase quickly discards the pairs of bodies that can't be colliding,
+/// so that the narrow phase only runs on the remaining ones.
+pub trait Broadphase {
+    /// Returns every pair `(i, j)`, with `i < j`, of indices into `bodies` whose bounds overlap.
+    /// Each pair is reported only once.
+    fn get_pairs(&mut self, bodies: &[RigidBody2D]) -> Vec<(usize, usize)>;
 }
@@ -0,0 +1,29 @@
+use crate::{collision::broadphase::Broadphase, rigidbody2d::RigidBody2D};
+
+/// Tests every pair of bodies against each other.
+/// Quadratic, but it's the reference the other broadphases are compared to.
+#[derive(Debug, Default)]
+pub struct BruteForce {}
+
+impl BruteForce {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Broadphase for BruteForce {
+    fn get_pairs(&mut self, bodies: &[RigidBody2D]) -> Vec<(usize, usize)> {
+        let aabbs: Vec<_> = bodies.iter().map(|body| body.world_aabb()).collect();
+        let mut pairs = vec![];
+
+        for (i, aabb) in aabbs.iter().enumerate() {
+            for (j, other) in aabbs.iter().enumerate().skip(i + 1) {
+                if aabb.overlaps(other) {
+                    pairs.push((i, j));
+                }
+            }
+        }
+
+        pairs
+    }
+}
@@ -0,0 +1,176 @@
+use std::{collections::HashMap, fmt::Display};
+
+use crate::{collision::broadphase::Broadphase, linalg::Vec2D, rigidbody2d::RigidBody2D};
+
+/// Uniform grid broadphase: each body is inserted in every cell its bounding box touches,
+/// and only the bodies sharing a cell are tested against each other.
+///
+/// Works best when the bodies have similar sizes, with a `cell_size` close to their diameter.
+#[derive(Debug)]
+pub struct SpatialHash {
+    /// Positive and finite, set through `new` only.
+    cell_size: f64,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+}
+
+/// Why a spatial hash can't be built with the given cell size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpatialHashError {
+    /// The cell size isn't a positive, finite number.
+    InvalidCellSize,
+}
+
+impl Display for SpatialHashError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidCellSize => write!(f, "the cells of a spatial hash must have a size"),
+        }
+    }
+}
+
+impl std::error::Error for SpatialHashError {}
+
+impl SpatialHash {
+    pub fn new(cell_size: f64) -> Result<Self, SpatialHashError> {
+        if !cell_size.is_finite() || cell_size <= 0. {
+            return Err(SpatialHashError::InvalidCellSize);
+        }
+
+        Ok(Self {
+            cell_size,
+            cells: HashMap::new(),
+        })
+    }
+
+    pub fn cell_size(&self) -> f64 {
+        self.cell_size
+    }
+
+    fn get_cell(&self, point: Vec2D) -> (i64, i64) {
+        (
+            (point.x / self.cell_size).floor() as i64,
+            (point.y / self.cell_size).floor() as i64,
+        )
+    }
+}
+
+impl Broadphase for SpatialHash {
+    fn get_pairs(&mut self, bodies: &[RigidBody2D]) -> Vec<(usize, usize)> {
+        // Keep the allocated buckets from one step to the next.
+        for bucket in self.cells.values_mut() {
+            bucket.clear();
+        }
+
+        let aabbs: Vec<_> = bodies.iter().map(|body| body.world_aabb()).collect();
+
+        for (index, aabb) in aabbs.iter().enumerate() {
+            let (min_x, min_y) = self.get_cell(aabb.min);
+            let (max_x, max_y) = self.get_cell(aabb.max);
+
+            for x in min_x..=max_x {
+                for y in min_y..=max_y {
+                    self.cells.entry((x, y)).or_default().push(index);
+                }
+            }
+        }
+
+        let mut pairs = vec![];
+
+        for (cell, bucket) in self.cells.iter() {
+            for (k, &i) in bucket.iter().enumerate() {
+                for &j in &bucket[(k + 1)..] {
+                    if !aabbs[i].overlaps(&aabbs[j]) {
+                        continue;
+                    }
+
+                    // Two bodies may share several cells: only report the pair in the cell
+                    // holding the lower corner of the intersection of their boxes.
+                    let corner = Vec2D::new(
+                        aabbs[i].min.x.max(aabbs[j].min.x),
+                        aabbs[i].min.y.max(aabbs[j].min.y),
+                    );
+
+                    if self.get_cell(corner) == *cell {
+                        pairs.push((i.min(j), i.max(j)));
+                    }
+                }
+            }
+        }
+
+        self.cells.retain(|_, bucket| !bucket.is_empty());
+
+        pairs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            broadphase::{
+                brute_force::BruteForce,
+                spatial_hash::{SpatialHash, SpatialHashError},
+                Broadphase,
+            },
+            collider::Collider,
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        let bodies = vec![
+            RigidBody2D::new(Vec2D::new(0., 0.), Collider::circle(1.), 1.),
+            RigidBody2D::new(Vec2D::new(1.5, 0.), Collider::circle(1.), 1.),
+            RigidBody2D::new(Vec2D::new(10., 10.), Collider::circle(1.), 1.),
+            // Spans a lot of cells, overlaps the two first ones.
+            RigidBody2D::new(Vec2D::new(0., 4.), Collider::rectangle(10., 8.), 1.),
+        ];
+
+        let mut pairs = SpatialHash::new(2.).unwrap().get_pairs(&bodies);
+        pairs.sort();
+
+        assert_eq!(pairs, vec![(0, 1), (0, 3), (1, 3)]);
+    }
+
+    #[test]
+    fn example_two() {
+        let mut bodies = vec![];
+
+        for i in 0..20 {
+            for j in 0..20 {
+                let position = Vec2D::new(i as f64 * 15. + (j % 3) as f64, j as f64 * 17.);
+                let collider = if (i + j) % 5 == 0 {
+                    Collider::rectangle(30., 12.)
+                } else {
+                    Collider::circle(8. + (i % 4) as f64)
+                };
+                bodies.push(RigidBody2D::new(position, collider, 1.));
+            }
+        }
+
+        let mut expected = BruteForce::new().get_pairs(&bodies);
+        expected.sort();
+
+        let mut spatial_hash = SpatialHash::new(20.).unwrap();
+
+        // Twice, to check the buckets are correctly reset between two calls.
+        for _ in 0..2 {
+            let mut pairs = spatial_hash.get_pairs(&bodies);
+            pairs.sort();
+
+            assert_eq!(pairs, expected);
+        }
+    }
+
+    #[test]
+    fn example_three() {
+        for cell_size in [0., -1., f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                SpatialHash::new(cell_size).unwrap_err(),
+                SpatialHashError::InvalidCellSize
+            );
+        }
+    }
+}
@@ -1,9 +1,236 @@
-use crate::linalg::Vec2D;
+use std::{
+    f64::consts::{PI, SQRT_2},
+    fmt::Display,
+    rc::Rc,
+};
+
+use crate::{
+    collision::{
+        aabb::Aabb,
+        algorithms::{
+            decomposition::{
+                decompose, is_convex, is_simple, remove_collinear, signed_area, width,
+            },
+            gjk::{gjk_collision, shape_cast},
+            heightfield::sample,
+            hull::convex_hull,
+            segment::closest_point_on_segment,
+        },
+        error::CollisionError,
+        ray::{
+            raycast_capsule, raycast_circle, raycast_heightfield, raycast_polygon, raycast_segment,
+            Ray, RayHit,
+        },
+        support_map::SupportMap,
+    },
+    linalg::{Mat22, Vec2D, TOLERANCE},
+    rigidbody2d::RigidBody2D,
+};
+
+/// The vertices of a rounded polygon pushed out to approximate its mass properties, which are the
+/// ones of the polygon when it isn't rounded.
+///
+/// From: https://github.com/erincatto/box2d/blob/main/src/geometry.c
+fn mass_vertices(vertices: &[Vec2D], radius: f64) -> Vec<Vec2D> {
+    if radius == 0. {
+        return vertices.to_vec();
+    }
+
+    let n = vertices.len();
+
+    (0..n)
+        .map(|i| {
+            let prev = vertices[(i + n - 1) % n];
+            let next = vertices[(i + 1) % n];
+            // Outward, the polygon being counter-clockwise
+            let normal_1 = (vertices[i] - prev).right().normalize();
+            let normal_2 = (next - vertices[i]).right().normalize();
+
+            vertices[i] + (normal_1 + normal_2).normalize() * (SQRT_2 * radius)
+        })
+        .collect()
+}
+
+/// The vertex the farthest along `direction`, the first one on a tie.
+fn farthest_vertex(vertices: &[Vec2D], direction: Vec2D) -> Vec2D {
+    let mut furthest = vertices[0];
+
+    for vertex in &vertices[1..] {
+        if *vertex * direction > furthest * direction {
+            furthest = *vertex;
+        }
+    }
+
+    furthest
+}
+
+/// Why a polygon given by the user can't be turned into a collider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PolygonError {
+    /// Fewer than three distinct vertices
+    TooFewVertices,
+    /// Every vertex is on the same line.
+    ZeroArea,
+    /// Two edges cross or touch each other.
+    SelfIntersecting,
+    /// A vertex is reflex, for a shape which must be convex.
+    NotConvex,
+    /// Narrower than `TOLERANCE`
+    TooSmall,
+}
+
+impl Display for PolygonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooFewVertices => write!(f, "a polygon needs at least three distinct vertices"),
+            Self::ZeroArea => write!(f, "the vertices of the polygon are all on the same line"),
+            Self::SelfIntersecting => write!(f, "the edges of the polygon intersect each other"),
+            Self::NotConvex => write!(f, "the polygon isn't convex"),
+            Self::TooSmall => write!(f, "the polygon is narrower than {TOLERANCE}"),
+        }
+    }
+}
+
+impl std::error::Error for PolygonError {}
+
+/// Why a chain given by the user can't be turned into a collider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainError {
+    /// Fewer than two vertices for an open chain, or three for a loop
+    TooFewVertices,
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooFewVertices => write!(
+                f,
+                "a chain needs at least two vertices, and a chain loop three"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// Why a heightfield given by the user can't be turned into a collider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeightfieldError {
+    /// Fewer than two samples
+    TooFewSamples,
+    /// The spacing isn't a positive, finite number.
+    InvalidSpacing,
+}
+
+impl Display for HeightfieldError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::TooFewSamples => write!(f, "a heightfield needs at least two samples"),
+            Self::InvalidSpacing => write!(f, "the spacing of a heightfield must be positive"),
+        }
+    }
+}
+
+impl std::error::Error for HeightfieldError {}
+
+/// Why the children given by the user can't be turned into a compound collider.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompoundError {
+    /// No child at all
+    Empty,
+    /// A child is itself a compound.
+    Nested,
+}
+
+impl Display for CompoundError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Empty => write!(f, "a compound needs at least one child"),
+            Self::Nested => write!(f, "the children of a compound can't be compounds"),
+        }
+    }
+}
+
+impl std::error::Error for CompoundError {}
+
+/// A shape of a compound collider, placed at `offset` and rotated by `angle` in the local space of
+/// the body.
+#[derive(Debug, Clone)]
+pub struct CompoundChild {
+    pub collider: Collider,
+    pub offset: Vec2D,
+    pub angle: f64,
+}
+
+impl CompoundChild {
+    pub fn new(collider: Collider, offset: Vec2D, angle: f64) -> Self {
+        Self {
+            collider,
+            offset,
+            angle,
+        }
+    }
+
+    /// From the local space of the child to the one of the body.
+    pub fn to_parent(&self, point: Vec2D) -> Vec2D {
+        self.offset + Mat22::from_angle(self.angle) * point
+    }
+
+    /// From the local space of the body to the one of the child.
+    pub fn to_child(&self, point: Vec2D) -> Vec2D {
+        Mat22::from_angle(-self.angle) * (point - self.offset)
+    }
+}
 
 #[derive(Debug, Clone)]
 pub enum Collider {
-    CircleCollider { radius: f64 },
-    PolygonCollider { vertices: Vec<Vec2D> },
+    CircleCollider {
+        radius: f64,
+    },
+    /// Convex, counter-clockwise (with the y axis pointing up). Build it with `Collider::polygon`
+    /// to enforce it.
+    /// Rounded by `radius`: the shape is every point closer than it to the polygon.
+    PolygonCollider {
+        vertices: Vec<Vec2D>,
+        radius: f64,
+    },
+    /// The segment going from `(-half_length, 0)` to `(half_length, 0)` rounded by `radius`.
+    CapsuleCollider {
+        half_length: f64,
+        radius: f64,
+    },
+    /// The ellipse with semi-axes `half_width` along x and `half_height` along y.
+    /// It has no edges: it collides through its support function only.
+    EllipseCollider {
+        half_width: f64,
+        half_height: f64,
+    },
+    /// A convex shape defined outside of the crate by its support function, collided through GJK
+    /// and EPA.
+    ConvexCollider {
+        shape: Rc<dyn SupportMap>,
+    },
+    /// A two-sided segment without thickness.
+    SegmentCollider {
+        start: Vec2D,
+        end: Vec2D,
+    },
+    /// A polyline made of two-sided segments, closed when `looped`.
+    /// Bodies sliding along it don't catch on the joints between its segments.
+    ChainCollider {
+        vertices: Vec<Vec2D>,
+        looped: bool,
+    },
+    /// Terrain going through the samples `(i * spacing, heights[i])`, for static bodies.
+    /// Only its cells under the other shapes are collided with them.
+    HeightfieldCollider {
+        heights: Vec<f64>,
+        spacing: f64,
+    },
+    /// Several shapes moving together, which can't be compounds themselves.
+    CompoundCollider {
+        children: Vec<CompoundChild>,
+    },
 }
 
 impl Collider {
@@ -19,35 +246,265 @@ impl Collider {
                 Vec2D::new(width / 2., height / 2.),
                 Vec2D::new(-width / 2., height / 2.),
             ],
+            radius: 0.,
+        }
+    }
+
+    /// The `width` by `height` rectangle rounded by `radius`, which makes it `2 * radius` wider
+    /// and taller.
+    pub fn rounded_rectangle(width: f64, height: f64, radius: f64) -> Self {
+        match Self::rectangle(width, height) {
+            Self::PolygonCollider { vertices, .. } => Self::PolygonCollider { vertices, radius },
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn capsule(half_length: f64, radius: f64) -> Self {
+        Self::CapsuleCollider {
+            half_length,
+            radius,
+        }
+    }
+
+    pub fn ellipse(half_width: f64, half_height: f64) -> Self {
+        Self::EllipseCollider {
+            half_width,
+            half_height,
+        }
+    }
+
+    pub fn convex(shape: impl SupportMap + 'static) -> Self {
+        Self::ConvexCollider {
+            shape: Rc::new(shape),
+        }
+    }
+
+    pub fn segment(start: Vec2D, end: Vec2D) -> Self {
+        Self::SegmentCollider { start, end }
+    }
+
+    /// The open polyline going through `vertices`.
+    pub fn chain(vertices: &[Vec2D]) -> Result<Self, ChainError> {
+        if vertices.len() < 2 {
+            return Err(ChainError::TooFewVertices);
+        }
+
+        Ok(Self::ChainCollider {
+            vertices: vertices.to_vec(),
+            looped: false,
+        })
+    }
+
+    /// The closed polyline going through `vertices`, and back to the first one.
+    pub fn chain_loop(vertices: &[Vec2D]) -> Result<Self, ChainError> {
+        if vertices.len() < 3 {
+            return Err(ChainError::TooFewVertices);
+        }
+
+        Ok(Self::ChainCollider {
+            vertices: vertices.to_vec(),
+            looped: true,
+        })
+    }
+
+    /// The terrain made of `heights` sampled every `spacing` along x, starting from the origin.
+    pub fn heightfield(heights: &[f64], spacing: f64) -> Result<Self, HeightfieldError> {
+        if heights.len() < 2 {
+            return Err(HeightfieldError::TooFewSamples);
+        }
+        if !spacing.is_finite() || spacing <= 0. {
+            return Err(HeightfieldError::InvalidSpacing);
+        }
+
+        Ok(Self::HeightfieldCollider {
+            heights: heights.to_vec(),
+            spacing,
+        })
+    }
+
+    pub fn compound(children: Vec<CompoundChild>) -> Result<Self, CompoundError> {
+        if children.is_empty() {
+            return Err(CompoundError::Empty);
+        }
+        if children
+            .iter()
+            .any(|child| matches!(child.collider, Self::CompoundCollider { .. }))
+        {
+            return Err(CompoundError::Nested);
+        }
+
+        Ok(Self::CompoundCollider { children })
+    }
+
+    /// The smallest convex polygon containing every point, counter-clockwise.
+    pub fn convex_hull(points: &[Vec2D]) -> Result<Self, PolygonError> {
+        if points.len() < 3 {
+            return Err(PolygonError::TooFewVertices);
+        }
+
+        let vertices = convex_hull(points);
+
+        if vertices.len() < 3 {
+            return Err(PolygonError::ZeroArea);
+        }
+
+        Ok(Self::PolygonCollider {
+            vertices,
+            radius: 0.,
+        })
+    }
+
+    /// Checks the polygon is simple, and puts it counter-clockwise without duplicated vertices.
+    fn simple_polygon(vertices: &[Vec2D]) -> Result<Vec<Vec2D>, PolygonError> {
+        let mut vertices = vertices.to_vec();
+
+        vertices.dedup_by(|a, b| (*a - *b).near_zero());
+        while vertices.len() > 1 && (vertices[0] - vertices[vertices.len() - 1]).near_zero() {
+            vertices.pop();
         }
+
+        if vertices.len() < 3 {
+            return Err(PolygonError::TooFewVertices);
+        }
+
+        let direction = vertices[1] - vertices[0];
+
+        if vertices
+            .iter()
+            .all(|v| direction.cross(*v - vertices[0]) == 0.)
+        {
+            return Err(PolygonError::ZeroArea);
+        }
+        if !is_simple(&vertices) {
+            return Err(PolygonError::SelfIntersecting);
+        }
+        if signed_area(&vertices) < 0. {
+            vertices.reverse();
+        }
+
+        Ok(vertices)
     }
 
+    /// A convex polygon given in either winding, checked, and cleaned of its duplicated and
+    /// collinear vertices up to `TOLERANCE`.
+    pub fn polygon(vertices: &[Vec2D]) -> Result<Self, PolygonError> {
+        let mut vertices = Self::simple_polygon(vertices)?;
+
+        if !is_convex(&vertices) {
+            return Err(PolygonError::NotConvex);
+        }
+        if width(&vertices) < TOLERANCE {
+            return Err(PolygonError::TooSmall);
+        }
+
+        remove_collinear(&mut vertices);
+
+        Ok(Self::PolygonCollider {
+            vertices,
+            radius: 0.,
+        })
+    }
+
+    /// The convex polygon rounded by `radius`, see `Collider::polygon`.
+    pub fn rounded_polygon(vertices: &[Vec2D], radius: f64) -> Result<Self, PolygonError> {
+        match Self::polygon(vertices)? {
+            Self::PolygonCollider { vertices, .. } => {
+                Ok(Self::PolygonCollider { vertices, radius })
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// The collider of any simple polygon, in either winding: the polygon itself when it's convex,
+    /// or a compound of convex pieces otherwise.
+    pub fn concave_polygon(vertices: &[Vec2D]) -> Result<Self, PolygonError> {
+        let vertices = Self::simple_polygon(vertices)?;
+
+        let mut pieces = decompose(&vertices);
+
+        if pieces.len() == 1 {
+            return Ok(Self::PolygonCollider {
+                vertices: pieces.pop().unwrap(),
+                radius: 0.,
+            });
+        }
+
+        // Several convex polygons: a valid compound.
+        Ok(Self::CompoundCollider {
+            children: pieces
+                .into_iter()
+                .map(|vertices| {
+                    let piece = Self::PolygonCollider {
+                        vertices,
+                        radius: 0.,
+                    };
+                    CompoundChild::new(piece, Vec2D::zero(), 0.)
+                })
+                .collect(),
+        })
+    }
+
+    /// Farthest point of the collider in `direction`.
+    /// Chains, heightfields and compounds aren't convex: this is the support of their convex hull.
     pub fn support(&self, direction: Vec2D) -> Vec2D {
         match self {
             Self::CircleCollider { radius } => direction * (radius / direction.norm()),
-            Self::PolygonCollider { vertices } => {
-                let mut furthest: Option<Vec2D> = None;
-
-                for vertex in vertices {
-                    match furthest {
-                        None => furthest = Some(*vertex),
-                        Some(point) => {
-                            if (*vertex) * direction > point * direction {
-                                furthest = Some(*vertex);
-                            }
-                        }
-                    }
+            Self::CapsuleCollider {
+                half_length,
+                radius,
+            } => {
+                let end = if direction.x >= 0. {
+                    *half_length
+                } else {
+                    -half_length
+                };
+                Vec2D::new(end, 0.) + direction * (radius / direction.norm())
+            }
+            Self::EllipseCollider {
+                half_width,
+                half_height,
+            } => {
+                // The point whose normal, scaled by the semi-axes, is the direction
+                let scaled = Vec2D::new(half_width * direction.x, half_height * direction.y);
+                Vec2D::new(half_width * scaled.x, half_height * scaled.y) / scaled.norm()
+            }
+            Self::ConvexCollider { shape } => shape.support(direction),
+            Self::SegmentCollider { start, end } => {
+                if *end * direction > *start * direction {
+                    *end
+                } else {
+                    *start
                 }
-
-                furthest.unwrap()
             }
+            Self::PolygonCollider { vertices, radius } if *radius > 0. => {
+                farthest_vertex(vertices, direction) + direction * (radius / direction.norm())
+            }
+            Self::PolygonCollider { vertices, .. } | Self::ChainCollider { vertices, .. } => {
+                farthest_vertex(vertices, direction)
+            }
+            Self::HeightfieldCollider { heights, spacing } => (0..heights.len())
+                .map(|i| sample(heights, *spacing, i))
+                .max_by(|a, b| (*a * direction).total_cmp(&(*b * direction)))
+                .unwrap(),
+            Self::CompoundCollider { children } => children
+                .iter()
+                .map(|child| {
+                    let local_direction = Mat22::from_angle(-child.angle) * direction;
+                    child.to_parent(child.collider.support(local_direction))
+                })
+                .max_by(|a, b| (*a * direction).total_cmp(&(*b * direction)))
+                .unwrap(),
         }
     }
 
     pub fn center(&self) -> Vec2D {
         match self {
-            Self::CircleCollider { .. } => Vec2D::zero(),
-            Self::PolygonCollider { vertices } => {
+            Self::CircleCollider { .. }
+            | Self::CapsuleCollider { .. }
+            | Self::EllipseCollider { .. } => Vec2D::zero(),
+            Self::ConvexCollider { shape } => shape.center(),
+            Self::SegmentCollider { start, end } => (*start + *end) / 2.,
+            Self::PolygonCollider { vertices, .. } | Self::ChainCollider { vertices, .. } => {
                 let mut i = 0.;
                 let mut center = Vec2D::zero();
                 for vertex in vertices {
@@ -56,10 +513,667 @@ impl Collider {
                 }
                 center / i
             }
+            Self::HeightfieldCollider { heights, spacing } => Vec2D::new(
+                spacing * (heights.len() - 1) as f64 / 2.,
+                heights.iter().sum::<f64>() / heights.len() as f64,
+            ),
+            Self::CompoundCollider { children } => {
+                let sum = children.iter().fold(Vec2D::zero(), |acc, child| {
+                    acc + child.to_parent(child.collider.center())
+                });
+                sum / children.len() as f64
+            }
+        }
+    }
+
+    /// Center of mass of the collider in its local space, for a uniform density.
+    pub fn centroid(&self) -> Vec2D {
+        match self {
+            Self::PolygonCollider { vertices, radius } => {
+                let vertices = mass_vertices(vertices, *radius);
+                let n = vertices.len();
+                let mut sum = Vec2D::zero();
+                let mut area = 0.;
+
+                for i in 0..n {
+                    let a = vertices[i];
+                    let b = vertices[(i + 1) % n];
+                    let cross = a.cross(b);
+
+                    sum += (a + b) * cross;
+                    area += cross;
+                }
+
+                if area == 0. {
+                    self.center()
+                } else {
+                    sum / (3. * area)
+                }
+            }
+            Self::CompoundCollider { children } => {
+                let area = self.area();
+
+                if area == 0. {
+                    return self.center();
+                }
+
+                children.iter().fold(Vec2D::zero(), |acc, child| {
+                    acc + child.to_parent(child.collider.centroid())
+                        * (child.collider.area() / area)
+                })
+            }
+            _ => self.center(),
+        }
+    }
+
+    /// The box bounding the collider in its local space.
+    pub fn local_aabb(&self) -> Aabb {
+        match self {
+            Self::CircleCollider { radius } => {
+                Aabb::new(Vec2D::new(-radius, -radius), Vec2D::new(*radius, *radius))
+            }
+            Self::PolygonCollider { vertices, radius } => {
+                Aabb::from_points(vertices).expand(*radius)
+            }
+            Self::ChainCollider { vertices, .. } => Aabb::from_points(vertices),
+            Self::HeightfieldCollider { heights, spacing } => Aabb::new(
+                Vec2D::new(0., heights.iter().copied().fold(f64::INFINITY, f64::min)),
+                Vec2D::new(
+                    spacing * (heights.len() - 1) as f64,
+                    heights.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+                ),
+            ),
+            Self::EllipseCollider {
+                half_width,
+                half_height,
+            } => Aabb::new(
+                Vec2D::new(-half_width, -half_height),
+                Vec2D::new(*half_width, *half_height),
+            ),
+            Self::ConvexCollider { shape } => shape.local_aabb(),
+            Self::SegmentCollider { start, end } => Aabb::from_points(&[*start, *end]),
+            Self::CompoundCollider { children } => children
+                .iter()
+                .map(|child| {
+                    let aabb = child.collider.local_aabb();
+                    let corners = [
+                        aabb.min,
+                        Vec2D::new(aabb.max.x, aabb.min.y),
+                        aabb.max,
+                        Vec2D::new(aabb.min.x, aabb.max.y),
+                    ];
+                    let corners: Vec<Vec2D> = corners.iter().map(|c| child.to_parent(*c)).collect();
+                    Aabb::from_points(&corners)
+                })
+                .reduce(|a, b| a.union(&b))
+                .unwrap(),
+            Self::CapsuleCollider {
+                half_length,
+                radius,
+            } => Aabb::new(
+                Vec2D::new(-half_length - radius, -radius),
+                Vec2D::new(half_length + radius, *radius),
+            ),
+        }
+    }
+
+    /// Casts a ray expressed in the local space of the collider.
+    /// Rays starting inside the collider don't hit it. Only fails when the shape cast against a
+    /// `ConvexCollider` does.
+    pub fn raycast(&self, ray: &Ray) -> Result<Option<RayHit>, CollisionError> {
+        let hit = match self {
+            Self::CircleCollider { radius } => raycast_circle(ray, Vec2D::zero(), *radius),
+            Self::PolygonCollider { vertices, radius } if *radius > 0. => {
+                if self.contains_point(ray.origin) {
+                    return Ok(None);
+                }
+
+                // The core, and a capsule around each edge for the rounded corners.
+                let n = vertices.len();
+
+                (0..n)
+                    .map(|i| raycast_capsule(ray, vertices[i], vertices[(i + 1) % n], *radius))
+                    .chain([raycast_polygon(ray, vertices)])
+                    .flatten()
+                    .min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+            }
+            Self::PolygonCollider { vertices, .. } => raycast_polygon(ray, vertices),
+            Self::CapsuleCollider {
+                half_length,
+                radius,
+            } => raycast_capsule(
+                ray,
+                Vec2D::new(-half_length, 0.),
+                Vec2D::new(*half_length, 0.),
+                *radius,
+            ),
+            Self::EllipseCollider {
+                half_width,
+                half_height,
+            } => {
+                // Against the unit circle, once the space is scaled by the semi-axes
+                let scale = |v: Vec2D| Vec2D::new(v.x / half_width, v.y / half_height);
+                let scaled_ray =
+                    Ray::new(scale(ray.origin), scale(ray.direction), ray.max_fraction);
+
+                raycast_circle(&scaled_ray, Vec2D::zero(), 1.).map(|hit| RayHit {
+                    fraction: hit.fraction,
+                    point: ray.point_at(hit.fraction),
+                    // Normals are scaled by the inverse transform.
+                    normal: scale(hit.normal).normalize(),
+                })
+            }
+            Self::ConvexCollider { shape } => {
+                // A point cast along the ray against the shape
+                let point = RigidBody2D::new(ray.origin, Self::circle(0.), 0.);
+
+                shape_cast(&point, &**shape, ray.direction * ray.max_fraction)?.map(|hit| RayHit {
+                    fraction: hit.fraction * ray.max_fraction,
+                    point: hit.point,
+                    normal: hit.normal,
+                })
+            }
+            Self::SegmentCollider { start, end } => raycast_segment(ray, *start, *end),
+            Self::ChainCollider { vertices, looped } => {
+                let n = vertices.len();
+                let count = if *looped { n } else { n - 1 };
+
+                (0..count)
+                    .filter_map(|i| raycast_segment(ray, vertices[i], vertices[(i + 1) % n]))
+                    .min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+            }
+            Self::HeightfieldCollider { heights, spacing } => {
+                raycast_heightfield(ray, heights, *spacing)
+            }
+            Self::CompoundCollider { children } => {
+                let mut closest: Option<RayHit> = None;
+
+                for child in children {
+                    let rotation = Mat22::from_angle(-child.angle);
+                    let local_ray = Ray::new(
+                        child.to_child(ray.origin),
+                        rotation * ray.direction,
+                        ray.max_fraction,
+                    );
+
+                    if let Some(hit) = child.collider.raycast(&local_ray)? {
+                        let is_closer = match closest {
+                            Some(best) => hit.fraction < best.fraction,
+                            None => true,
+                        };
+
+                        if is_closer {
+                            closest = Some(RayHit {
+                                fraction: hit.fraction,
+                                point: child.to_parent(hit.point),
+                                normal: Mat22::from_angle(child.angle) * hit.normal,
+                            });
+                        }
+                    }
+                }
+
+                closest
+            }
+        };
+
+        Ok(hit)
+    }
+
+    /// Whether `point`, expressed in the local space of the collider, is inside it.
+    pub fn contains_point(&self, point: Vec2D) -> bool {
+        match self {
+            Self::CircleCollider { radius } => point.squared_norm() <= radius * radius,
+            Self::PolygonCollider { vertices, radius } => {
+                let n = vertices.len();
+
+                if *radius > 0.
+                    && (0..n).any(|i| {
+                        let (closest, _) =
+                            closest_point_on_segment(point, vertices[i], vertices[(i + 1) % n]);
+                        (point - closest).squared_norm() <= radius * radius
+                    })
+                {
+                    return true;
+                }
+
+                let mut sign = 0.;
+
+                // The point must be on the same side of every edge, whatever the winding.
+                for i in 0..n {
+                    let j = (i + 1) % n;
+                    let side = (vertices[j] - vertices[i]).cross(point - vertices[i]);
+
+                    if side * sign < 0. {
+                        return false;
+                    }
+                    if side != 0. {
+                        sign = side;
+                    }
+                }
+
+                true
+            }
+            Self::CapsuleCollider {
+                half_length,
+                radius,
+            } => {
+                let (closest, _) = closest_point_on_segment(
+                    point,
+                    Vec2D::new(-half_length, 0.),
+                    Vec2D::new(*half_length, 0.),
+                );
+                (point - closest).squared_norm() <= radius * radius
+            }
+            Self::EllipseCollider {
+                half_width,
+                half_height,
+            } => (point.x / half_width).powi(2) + (point.y / half_height).powi(2) <= 1.,
+            Self::ConvexCollider { shape } => {
+                let point = RigidBody2D::new(point, Self::circle(0.), 0.);
+                matches!(gjk_collision(&point, &**shape), Ok(Some(_)))
+            }
+            // Without area, nothing is inside.
+            Self::SegmentCollider { .. }
+            | Self::ChainCollider { .. }
+            | Self::HeightfieldCollider { .. } => false,
+            Self::CompoundCollider { children } => children
+                .iter()
+                .any(|child| child.collider.contains_point(child.to_child(point))),
+        }
+    }
+
+    pub fn area(&self) -> f64 {
+        match self {
+            Self::CircleCollider { radius } => PI * radius * radius,
+            Self::PolygonCollider { vertices, radius } => {
+                let n = vertices.len();
+                let mut area = 0.;
+                let mut perimeter = 0.;
+                for i in 0..n {
+                    area += vertices[i].cross(vertices[(i + 1) % n]);
+                    perimeter += (vertices[(i + 1) % n] - vertices[i]).norm();
+                }
+                // The rounding adds a band along each edge, and a disc split between the corners.
+                area.abs() / 2. + perimeter * radius + PI * radius * radius
+            }
+            Self::CapsuleCollider {
+                half_length,
+                radius,
+            } => PI * radius * radius + 4. * half_length * radius,
+            Self::EllipseCollider {
+                half_width,
+                half_height,
+            } => PI * half_width * half_height,
+            Self::ConvexCollider { shape } => shape.area(),
+            Self::SegmentCollider { .. }
+            | Self::ChainCollider { .. }
+            | Self::HeightfieldCollider { .. } => 0.,
+            Self::CompoundCollider { children } => {
+                children.iter().map(|child| child.collider.area()).sum()
+            }
+        }
+    }
+
+    /// Moment of inertia around the local origin of the collider, for a uniform density.
+    /// Segments, chains and heightfields are meant for static bodies and don't have any.
+    ///
+    /// From: https://github.com/erincatto/box2d/blob/main/src/geometry.c
+    pub fn get_inertia(&self, mass: f64) -> f64 {
+        match self {
+            Self::CircleCollider { radius } => mass * radius * radius / 2.,
+            Self::PolygonCollider { vertices, radius } => {
+                let vertices = mass_vertices(vertices, *radius);
+
+                // Sum over the triangles made by each edge and the origin.
+                let n = vertices.len();
+                let mut numerator = 0.;
+                let mut denominator = 0.;
+
+                for i in 0..n {
+                    let a = vertices[i];
+                    let b = vertices[(i + 1) % n];
+                    let cross = a.cross(b);
+
+                    numerator += cross * (a * a + a * b + b * b);
+                    denominator += cross;
+                }
+
+                if denominator == 0. {
+                    0.
+                } else {
+                    mass * numerator / (6. * denominator)
+                }
+            }
+            Self::CapsuleCollider {
+                half_length,
+                radius,
+            } => {
+                let density = mass / self.area();
+                let rr = radius * radius;
+                let length = 2. * half_length;
+
+                let circle_mass = density * PI * rr;
+                let box_mass = density * 2. * radius * length;
+
+                // The two half discs, shifted to the ends of the box with the parallel axis theorem.
+                let lc = 4. * radius / (3. * PI);
+                let circle_inertia =
+                    circle_mass * (0.5 * rr + half_length * half_length + 2. * half_length * lc);
+                let box_inertia = box_mass * (4. * rr + length * length) / 12.;
+
+                circle_inertia + box_inertia
+            }
+            Self::EllipseCollider {
+                half_width,
+                half_height,
+            } => mass * (half_width * half_width + half_height * half_height) / 4.,
+            Self::ConvexCollider { shape } => shape.inertia(mass),
+            Self::SegmentCollider { .. }
+            | Self::ChainCollider { .. }
+            | Self::HeightfieldCollider { .. } => 0.,
+            Self::CompoundCollider { children } => {
+                let area = self.area();
+
+                if area == 0. {
+                    return 0.;
+                }
+
+                // The mass is shared according to the area of each child, whose inertia is moved
+                // from its own origin to the one of the body with the parallel axis theorem.
+                children
+                    .iter()
+                    .map(|child| {
+                        let child_mass = mass * child.collider.area() / area;
+                        let centroid = child.collider.centroid();
+                        let shift =
+                            child.to_parent(centroid).squared_norm() - centroid.squared_norm();
+
+                        child.collider.get_inertia(child_mass) + child_mass * shift
+                    })
+                    .sum()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::{
+                ChainError, Collider, CompoundChild, CompoundError, HeightfieldError, PolygonError,
+            },
+            ray::Ray,
+        },
+        linalg::Vec2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // Two squares side by side weigh as much as the rectangle they make.
+        let compound = Collider::compound(vec![
+            CompoundChild::new(Collider::rectangle(1., 1.), Vec2D::new(-0.5, 0.), 0.),
+            CompoundChild::new(Collider::rectangle(1., 1.), Vec2D::new(0.5, 0.), 0.),
+        ])
+        .unwrap();
+        let rectangle = Collider::rectangle(2., 1.);
+
+        assert_eq!(compound.area(), rectangle.area());
+        assert!((compound.get_inertia(3.) - rectangle.get_inertia(3.)).abs() < 1e-9);
+        assert_eq!(compound.centroid(), Vec2D::zero());
+        assert_eq!(compound.local_aabb(), rectangle.local_aabb());
+    }
+
+    #[test]
+    fn example_two() {
+        // An L made of a vertical and a rotated horizontal bar
+        let compound = Collider::compound(vec![
+            CompoundChild::new(Collider::rectangle(1., 3.), Vec2D::new(0.5, 1.5), 0.),
+            CompoundChild::new(
+                Collider::rectangle(1., 2.),
+                Vec2D::new(2., 0.5),
+                std::f64::consts::FRAC_PI_2,
+            ),
+        ])
+        .unwrap();
+
+        assert_eq!(compound.area(), 5.);
+        assert!((compound.centroid() - Vec2D::new(1.1, 1.1)).near_zero());
+
+        assert!(compound.contains_point(Vec2D::new(2.5, 0.5)));
+        assert!(compound.contains_point(Vec2D::new(0.5, 2.5)));
+        assert!(!compound.contains_point(Vec2D::new(2., 2.)));
+
+        // Each bar about the origin, from the inertia of a rectangle about its center
+        let bar =
+            |m: f64, w: f64, h: f64, c: Vec2D| m * (w * w + h * h) / 12. + m * c.squared_norm();
+        let expected = bar(3., 1., 3., Vec2D::new(0.5, 1.5)) + bar(2., 2., 1., Vec2D::new(2., 0.5));
+
+        assert!((compound.get_inertia(5.) - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn example_three() {
+        // Clockwise, with the first vertex repeated at the end
+        let l = Collider::concave_polygon(&[
+            Vec2D::new(0., 0.),
+            Vec2D::new(0., 3.),
+            Vec2D::new(1., 3.),
+            Vec2D::new(1., 1.),
+            Vec2D::new(3., 1.),
+            Vec2D::new(3., 0.),
+            Vec2D::new(0., 0.),
+        ])
+        .unwrap();
+
+        match &l {
+            Collider::CompoundCollider { children } => assert_eq!(children.len(), 2),
+            _ => panic!("An L isn't convex."),
+        }
+        assert_eq!(l.area(), 5.);
+        assert!(l.contains_point(Vec2D::new(2., 0.5)));
+        assert!(!l.contains_point(Vec2D::new(2., 2.)));
+
+        assert!(matches!(
+            Collider::concave_polygon(&[
+                Vec2D::new(0., 0.),
+                Vec2D::new(1., 0.),
+                Vec2D::new(0., 1.)
+            ]),
+            Ok(Collider::PolygonCollider { .. })
+        ));
+
+        assert_eq!(
+            Collider::concave_polygon(&[
+                Vec2D::new(0., 0.),
+                Vec2D::new(1., 0.),
+                Vec2D::new(1., 0.)
+            ])
+            .unwrap_err(),
+            PolygonError::TooFewVertices
+        );
+        assert_eq!(
+            Collider::concave_polygon(&[
+                Vec2D::new(0., 0.),
+                Vec2D::new(1., 1.),
+                Vec2D::new(2., 2.)
+            ])
+            .unwrap_err(),
+            PolygonError::ZeroArea
+        );
+        assert_eq!(
+            Collider::concave_polygon(&[
+                Vec2D::new(0., 0.),
+                Vec2D::new(1., 1.),
+                Vec2D::new(1., 0.),
+                Vec2D::new(0., 1.),
+            ])
+            .unwrap_err(),
+            PolygonError::SelfIntersecting
+        );
+    }
+
+    #[test]
+    fn example_four() {
+        // Clockwise, with a duplicated and a collinear vertex
+        let square = Collider::polygon(&[
+            Vec2D::new(0., 0.),
+            Vec2D::new(0., 2.),
+            Vec2D::new(2., 2.),
+            Vec2D::new(2., 2.0001),
+            Vec2D::new(2., 1.),
+            Vec2D::new(2., 0.),
+        ])
+        .unwrap();
+
+        match square {
+            Collider::PolygonCollider { vertices, .. } => assert_eq!(
+                vertices,
+                vec![
+                    Vec2D::new(2., 0.),
+                    Vec2D::new(2., 2.),
+                    Vec2D::new(0., 2.),
+                    Vec2D::new(0., 0.),
+                ]
+            ),
+            _ => panic!("A square is a polygon."),
         }
+
+        let l = [
+            Vec2D::new(0., 0.),
+            Vec2D::new(3., 0.),
+            Vec2D::new(3., 1.),
+            Vec2D::new(1., 1.),
+            Vec2D::new(1., 3.),
+            Vec2D::new(0., 3.),
+        ];
+        assert_eq!(Collider::polygon(&l).unwrap_err(), PolygonError::NotConvex);
+
+        let sliver = [
+            Vec2D::new(0., 0.),
+            Vec2D::new(10., 0.),
+            Vec2D::new(5., 0.0005),
+        ];
+        assert_eq!(
+            Collider::polygon(&sliver).unwrap_err(),
+            PolygonError::TooSmall
+        );
+        assert_eq!(
+            PolygonError::TooSmall.to_string(),
+            "the polygon is narrower than 0.001"
+        );
+    }
+
+    #[test]
+    fn example_five() {
+        let rounded = Collider::rounded_rectangle(2., 2., 0.5);
+
+        assert_eq!(rounded.area(), 4. + 8. * 0.5 + std::f64::consts::PI * 0.25);
+        assert_eq!(
+            rounded.local_aabb(),
+            Collider::rectangle(3., 3.).local_aabb()
+        );
+        assert!((rounded.support(Vec2D::new(1., 1.))
+            - Vec2D::new(1., 1.) * (1. + 0.5 / 2f64.sqrt()))
+        .near_zero());
+        // The corners are pushed out as far as the ones of the bounding square.
+        assert!((rounded.get_inertia(1.) - 1.5).abs() < 1e-9);
+
+        assert!(rounded.contains_point(Vec2D::new(1.3, 0.)));
+        assert!(rounded.contains_point(Vec2D::new(1.3, 1.3)));
+        assert!(!rounded.contains_point(Vec2D::new(1.4, 1.4)));
+
+        // Across the rounded corner
+        let hit = rounded
+            .raycast(&Ray::new(Vec2D::new(-5., 1.2), Vec2D::new(1., 0.), 10.))
+            .unwrap()
+            .unwrap();
+        let x = 0.5f64.powi(2) - 0.2f64.powi(2);
+
+        assert!((hit.point - Vec2D::new(-1. - x.sqrt(), 1.2)).near_zero());
+        assert!((hit.normal - Vec2D::new(-x.sqrt(), 0.2) / 0.5).near_zero());
+        assert!(rounded
+            .raycast(&Ray::new(Vec2D::new(-1.2, 0.), Vec2D::new(1., 0.), 10.))
+            .unwrap()
+            .is_none());
     }
 
-    pub fn get_inertia(&self) -> f64 {
-        1.
+    #[test]
+    fn example_six() {
+        let ellipse = Collider::ellipse(2., 1.);
+
+        assert_eq!(ellipse.support(Vec2D::new(1., 0.)), Vec2D::new(2., 0.));
+        assert_eq!(ellipse.support(Vec2D::new(0., -3.)), Vec2D::new(0., -1.));
+        // Where the normal of the ellipse is the diagonal
+        let support = ellipse.support(Vec2D::new(1., 1.));
+        assert!((support - Vec2D::new(4., 1.) / 5f64.sqrt()).near_zero());
+
+        assert!(ellipse.contains_point(Vec2D::new(1.9, 0.)));
+        assert!(!ellipse.contains_point(Vec2D::new(1.5, 0.9)));
+
+        let hit = ellipse
+            .raycast(&Ray::new(Vec2D::new(-4., 0.), Vec2D::new(1., 0.), 10.))
+            .unwrap()
+            .unwrap();
+        assert_eq!(hit.fraction, 2.);
+        assert_eq!(hit.normal, Vec2D::new(-1., 0.));
+
+        let hit = ellipse
+            .raycast(&Ray::new(Vec2D::new(4., 4.), Vec2D::new(-1., -1.), 10.))
+            .unwrap()
+            .unwrap();
+        let x = 2. / 5f64.sqrt();
+        assert!((hit.point - Vec2D::new(x, x)).near_zero());
+        assert!((hit.normal - Vec2D::new(1., 4.).normalize()).near_zero());
+    }
+
+    #[test]
+    fn example_seven() {
+        let (a, b, c) = (Vec2D::new(0., 0.), Vec2D::new(1., 0.), Vec2D::new(1., 1.));
+
+        assert_eq!(
+            Collider::chain(&[a]).unwrap_err(),
+            ChainError::TooFewVertices
+        );
+        assert!(Collider::chain(&[a, b]).is_ok());
+
+        assert_eq!(
+            Collider::chain_loop(&[a, b]).unwrap_err(),
+            ChainError::TooFewVertices
+        );
+        assert!(Collider::chain_loop(&[a, b, c]).is_ok());
+    }
+
+    #[test]
+    fn example_eight() {
+        assert_eq!(
+            Collider::heightfield(&[0.], 1.).unwrap_err(),
+            HeightfieldError::TooFewSamples
+        );
+
+        for spacing in [0., -1., f64::NAN, f64::INFINITY] {
+            assert_eq!(
+                Collider::heightfield(&[0., 1.], spacing).unwrap_err(),
+                HeightfieldError::InvalidSpacing
+            );
+        }
+        assert!(Collider::heightfield(&[0., 1.], 0.5).is_ok());
+    }
+
+    #[test]
+    fn example_nine() {
+        assert_eq!(
+            Collider::compound(vec![]).unwrap_err(),
+            CompoundError::Empty
+        );
+
+        let child = CompoundChild::new(Collider::circle(1.), Vec2D::zero(), 0.);
+        let compound = Collider::compound(vec![child.clone()]).unwrap();
+        let nested = CompoundChild::new(compound, Vec2D::new(2., 0.), 0.);
+
+        assert_eq!(
+            Collider::compound(vec![child, nested]).unwrap_err(),
+            CompoundError::Nested
+        );
     }
 }
@@ -0,0 +1,258 @@
+use crate::{
+    collision::{
+        aabb::Aabb,
+        algorithms::{
+            epa::epa,
+            gjk::{gjk_proximity, Proximity},
+            segment::{chain_segments, heightfield_segments},
+        },
+        collider::Collider::{ChainCollider, HeightfieldCollider, SegmentCollider},
+        error::CollisionError,
+    },
+    linalg::Vec2D,
+    rigidbody2d::RigidBody2D,
+};
+
+/// The closest points between two shapes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Distance {
+    /// The distance between the shapes, or minus their penetration depth when they overlap.
+    pub separation: f64,
+    pub point_a: Vec2D,
+    pub point_b: Vec2D,
+    /// Unit vector going from `a` to `b`: moving `b` along it separates them.
+    pub normal: Vec2D,
+}
+
+/// The convex pieces of `body`, as bodies: the children of a compound, and the segments of a
+/// chain. Only the cells of a heightfield overlapping `region` are kept, when given.
+pub fn convex_parts(
+    body: &RigidBody2D,
+    region: Option<&Aabb>,
+) -> Result<Vec<RigidBody2D>, CollisionError> {
+    let mut parts = vec![];
+
+    for (_, child) in body.child_bodies() {
+        let segments = match (&child.collider, region) {
+            (HeightfieldCollider { .. }, Some(region)) => heightfield_segments(&child, region)?,
+            (SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. }, _) => {
+                chain_segments(&child)?
+            }
+            _ => {
+                parts.push(child);
+                continue;
+            }
+        };
+
+        // Segments are in world space.
+        parts.extend(segments.iter().map(|segment| {
+            RigidBody2D::new(
+                Vec2D::zero(),
+                SegmentCollider {
+                    start: segment.start,
+                    end: segment.end,
+                },
+                0.,
+            )
+        }));
+    }
+
+    Ok(parts)
+}
+
+/// The distance between two convex shapes, through EPA when they overlap.
+fn convex_distance(a: &RigidBody2D, b: &RigidBody2D) -> Result<Distance, CollisionError> {
+    match gjk_proximity(a, b, f64::INFINITY)? {
+        Proximity::Separated(distance) => Ok(distance),
+        Proximity::Overlapping(simplex) => {
+            let result = epa(simplex, a, b)?;
+
+            // EPA pushes `a` out of `b`.
+            Ok(Distance {
+                separation: -result.depth,
+                point_a: result.point_a,
+                point_b: result.point_b,
+                normal: -result.normal,
+            })
+        }
+        Proximity::Beyond => unreachable!(),
+    }
+}
+
+/// The distance between any two colliders, with their closest points and the direction
+/// separating them. For compounds, chains and heightfields, it is the one of their closest
+/// pieces.
+pub fn distance(a: &RigidBody2D, b: &RigidBody2D) -> Result<Distance, CollisionError> {
+    let (parts_a, parts_b) = (convex_parts(a, None)?, convex_parts(b, None)?);
+    let aabbs_b: Vec<Aabb> = parts_b.iter().map(RigidBody2D::world_aabb).collect();
+
+    // The pairs with the closest boxes first: once one is measured, the pieces with farther
+    // boxes, like most cells of a heightfield, are ruled out without running GJK.
+    let mut pairs = vec![];
+    for (i, part_a) in parts_a.iter().enumerate() {
+        let aabb_a = part_a.world_aabb();
+
+        for (j, aabb_b) in aabbs_b.iter().enumerate() {
+            pairs.push((aabb_a.distance(aabb_b), i, j));
+        }
+    }
+    pairs.sort_by(|(a, ..), (b, ..)| a.total_cmp(b));
+
+    let mut closest: Option<Distance> = None;
+
+    for (gap, i, j) in pairs {
+        if closest.is_some_and(|closest| gap > closest.separation) {
+            break;
+        }
+
+        let distance = convex_distance(&parts_a[i], &parts_b[j])?;
+
+        if closest.is_none_or(|closest| distance.separation < closest.separation) {
+            closest = Some(distance);
+        }
+    }
+
+    // Every collider has at least one piece.
+    Ok(closest.unwrap())
+}
+
+/// Whether two colliders are at most `margin` apart, overlapping ones included. Cheaper than
+/// `distance`: bounding boxes rule most pairs out, and GJK stops once it knows the answer.
+pub fn is_within(a: &RigidBody2D, b: &RigidBody2D, margin: f64) -> Result<bool, CollisionError> {
+    let (aabb_a, aabb_b) = (a.world_aabb().expand(margin), b.world_aabb().expand(margin));
+
+    if !aabb_a.overlaps(&b.world_aabb()) {
+        return Ok(false);
+    }
+
+    for part_a in convex_parts(a, Some(&aabb_b))? {
+        let part_aabb = part_a.world_aabb().expand(margin);
+
+        for part_b in convex_parts(b, Some(&aabb_a))? {
+            if !part_aabb.overlaps(&part_b.world_aabb()) {
+                continue;
+            }
+
+            match gjk_proximity(&part_a, &part_b, margin)? {
+                Proximity::Overlapping(_) => return Ok(true),
+                Proximity::Separated(distance) if distance.separation <= margin => return Ok(true),
+                _ => (),
+            }
+        }
+    }
+
+    Ok(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::{Collider, CompoundChild},
+            distance::{distance, is_within},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // Two boxes, the second one turned on its corner
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
+        let mut b = RigidBody2D::new(Vec2D::new(0.3, 3.), Collider::rectangle(2., 2.), 1.);
+        b.angle = std::f64::consts::FRAC_PI_4;
+
+        let result = distance(&a, &b).unwrap();
+
+        assert!((result.separation - (2. - std::f64::consts::SQRT_2)).abs() < 1e-9);
+        assert!((result.point_a - Vec2D::new(0.3, 1.)).near_zero());
+        assert!((result.point_b - Vec2D::new(0.3, 3. - std::f64::consts::SQRT_2)).near_zero());
+        assert!((result.normal - Vec2D::new(0., 1.)).near_zero());
+
+        // Neither of them is a point, the other way around.
+        let result = distance(&b, &a).unwrap();
+        assert!((result.separation - (2. - std::f64::consts::SQRT_2)).abs() < 1e-9);
+        assert!((result.normal - Vec2D::new(0., -1.)).near_zero());
+
+        // Curved shapes
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(3., 4.), Collider::capsule(1., 0.5), 1.);
+
+        let result = distance(&a, &b).unwrap();
+        let expected = Vec2D::new(2., 4.).norm() - 1.5;
+
+        assert!((result.separation - expected).abs() < 1e-6);
+        assert!((result.point_a - Vec2D::new(2., 4.).normalize()).norm() < 1e-3);
+        assert!((result.normal - Vec2D::new(2., 4.).normalize()).norm() < 1e-3);
+    }
+
+    #[test]
+    fn example_two() {
+        // Overlapping: minus the penetration depth
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(0.5, 1.8), Collider::rectangle(2., 2.), 1.);
+
+        let result = distance(&a, &b).unwrap();
+
+        assert!((result.separation + 0.2).abs() < 1e-9);
+        assert!((result.normal - Vec2D::new(0., 1.)).near_zero());
+
+        // The closest piece of a compound, and of a chain
+        let l = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::compound(vec![
+                CompoundChild::new(Collider::rectangle(4., 1.), Vec2D::new(0., 0.), 0.),
+                CompoundChild::new(Collider::rectangle(1., 4.), Vec2D::new(-1.5, 2.5), 0.),
+            ])
+            .unwrap(),
+            1.,
+        );
+        let circle = RigidBody2D::new(Vec2D::new(1., 3.), Collider::circle(0.5), 1.);
+
+        let result = distance(&l, &circle).unwrap();
+        // Closer to the side of the upright than to the top of the base
+        assert!((result.separation - 1.5).abs() < 1e-6);
+        assert!((result.normal - Vec2D::new(1., 0.)).near_zero());
+
+        let ground = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::chain(&[Vec2D::new(-4., 0.), Vec2D::new(0., 2.), Vec2D::new(4., 0.)])
+                .unwrap(),
+            0.,
+        );
+        let circle = RigidBody2D::new(Vec2D::new(0., 4.), Collider::circle(0.5), 1.);
+
+        let result = distance(&circle, &ground).unwrap();
+        assert!((result.separation - 1.5).abs() < 1e-6);
+        assert!((result.point_b - Vec2D::new(0., 2.)).near_zero());
+    }
+
+    #[test]
+    fn example_three() {
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(2.5, 0.), Collider::rectangle(1., 1.), 1.);
+
+        // One apart
+        assert!(is_within(&a, &b, 1.01).unwrap());
+        assert!(!is_within(&a, &b, 0.99).unwrap());
+        assert!(!is_within(&a, &b, 0.).unwrap());
+
+        let c = RigidBody2D::new(Vec2D::new(1.4, 0.), Collider::rectangle(1., 1.), 1.);
+        assert!(is_within(&a, &c, 0.).unwrap());
+
+        // Only the cells of the terrain nearby are looked at.
+        let terrain = RigidBody2D::new(
+            Vec2D::new(-1000., 0.),
+            Collider::heightfield(&[0.; 2000], 1.).unwrap(),
+            0.,
+        );
+        let d = RigidBody2D::new(Vec2D::new(0.5, 1.5), Collider::circle(1.), 1.);
+
+        assert!(is_within(&terrain, &d, 0.6).unwrap());
+        assert!(!is_within(&terrain, &d, 0.4).unwrap());
+
+        let result = distance(&d, &terrain).unwrap();
+        assert!((result.separation - 0.5).abs() < 1e-6);
+        assert!((result.point_b - Vec2D::new(0.5, 0.)).near_zero());
+    }
+}
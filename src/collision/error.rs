@@ -0,0 +1,63 @@
+use std::fmt::Display;
+
+use crate::{collision::collider::PolygonError, linalg::LinalgError};
+
+/// Why a collision query between two shapes failed. They come from numerical breakdowns
+/// (non-finite positions, degenerate shapes) rather than from the shapes not touching: the
+/// engine decides whether the pair is skipped, logged or the step aborted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionError {
+    /// A support point has a non-finite coordinate, usually because a body has a NaN position.
+    InvalidSupportPoint,
+    /// The search direction vanished, so GJK can't pick the next support point.
+    DegenerateDirection,
+    /// The simplex collapsed into a point or a segment through the origin, with no support
+    /// point to expand it.
+    DegenerateSimplex,
+    /// An iterative algorithm ran out of iterations.
+    NoConvergence,
+    /// The narrow phase routine doesn't handle this pair of colliders.
+    UnsupportedPair,
+    /// A joint ties a body to itself or to a body the engine doesn't have, or a prismatic joint
+    /// has no axis.
+    InvalidJoint,
+    Polygon(PolygonError),
+    Linalg(LinalgError),
+}
+
+impl Display for CollisionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidSupportPoint => write!(f, "a support point isn't finite"),
+            Self::DegenerateDirection => write!(f, "the search direction is zero or not finite"),
+            Self::DegenerateSimplex => write!(f, "the simplex can't be expanded around the origin"),
+            Self::NoConvergence => write!(f, "the algorithm ran out of iterations"),
+            Self::UnsupportedPair => write!(f, "the narrow phase can't handle these colliders"),
+            Self::InvalidJoint => write!(f, "the joint has no axis or doesn't tie two bodies"),
+            Self::Polygon(err) => write!(f, "invalid polygon: {err}"),
+            Self::Linalg(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for CollisionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Polygon(err) => Some(err),
+            Self::Linalg(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<PolygonError> for CollisionError {
+    fn from(err: PolygonError) -> Self {
+        Self::Polygon(err)
+    }
+}
+
+impl From<LinalgError> for CollisionError {
+    fn from(err: LinalgError) -> Self {
+        Self::Linalg(err)
+    }
+}
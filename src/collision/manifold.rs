@@ -1,21 +1,98 @@
 use crate::{
-    collision::collider::Collider::{CircleCollider, PolygonCollider},
+    collision::{
+        collider::Collider::{
+            CapsuleCollider, ChainCollider, CircleCollider, CompoundCollider, HeightfieldCollider,
+            PolygonCollider, SegmentCollider,
+        },
+        error::CollisionError,
+    },
     linalg::Vec2D,
     rigidbody2d::RigidBody2D,
 };
 
 use super::narrow::{
+    capsule_v_capsule::capsule_v_capsule, capsule_v_polygon::capsule_v_polygon,
+    capsule_v_sphere::capsule_v_sphere, chain_v_shape::chain_v_shape, gjk_v_shape::gjk_v_shape,
     polygon_v_polygon::polygon_v_polygon, sphere_v_polygon::sphere_v_polygon,
     sphere_v_sphere::sphere_v_sphere,
 };
 
+/// A contact found by the algorithms shared between several pairs: the normal, going from `a` to
+/// `b`, and the points half way between the surfaces with their depth.
+pub type Contact = (Vec2D, Vec<(Vec2D, f64)>);
+
+/// How the shapes without a routine of their own, colliding through their support functions,
+/// find their penetration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PenetrationAlgorithm {
+    /// GJK, then EPA: the exact depth.
+    #[default]
+    Epa,
+    /// Minkowski Portal Refinement: fewer support points, but the depth is measured along the
+    /// line between the centers.
+    Mpr,
+}
+
+/// The body whose surface a contact point lies on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactBody {
+    A,
+    B,
+}
+
+/// A feature of a polygon: one of its vertices, or the edge going from that vertex to the next.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContactFeature {
+    Vertex(usize),
+    Edge(usize),
+}
+
+/// A point of contact, on the surface of one of the bodies, with how deep it is inside the other.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ContactPoint {
+    pub point: Vec2D,
+    /// Along the normal, never negative.
+    pub depth: f64,
+    pub body: ContactBody,
+    /// The feature of the polygon the contact comes from, for the routines keeping track of it.
+    pub feature: Option<ContactFeature>,
+}
+
+impl ContactPoint {
+    /// The point on the surface of `a`, for the normal of the manifold.
+    pub fn on_a(&self, normal: Vec2D) -> Vec2D {
+        match self.body {
+            ContactBody::A => self.point,
+            ContactBody::B => self.point + normal * self.depth,
+        }
+    }
+
+    /// The point on the surface of `b`, for the normal of the manifold.
+    pub fn on_b(&self, normal: Vec2D) -> Vec2D {
+        match self.body {
+            ContactBody::A => self.point - normal * self.depth,
+            ContactBody::B => self.point,
+        }
+    }
+}
+
+/// The contact between `a` and `b`. Every narrow phase routine follows the same conventions,
+/// whatever the order of the shapes: the normal is a unit vector going from `a` to `b`, the depths
+/// are positive, and each point lies on the surface of the body it is tagged with. Moving `b`
+/// along the normal by the depth of a point separates the bodies there.
 #[derive(Debug)]
 pub struct ContactManifold<'a> {
     pub a: &'a RigidBody2D,
     pub b: &'a RigidBody2D,
 
+    /// The children of `a` and `b` in contact, when they are compound colliders.
+    pub child_a: Option<usize>,
+    pub child_b: Option<usize>,
+
     pub normal: Option<Vec2D>,
-    pub points: Vec<(Vec2D, f64)>,
+    pub points: Vec<ContactPoint>,
+
+    pub penetration: PenetrationAlgorithm,
 }
 
 impl<'a> ContactManifold<'a> {
@@ -23,17 +100,126 @@ impl<'a> ContactManifold<'a> {
         ContactManifold {
             a,
             b,
+            child_a: None,
+            child_b: None,
             normal: None,
             points: vec![],
+            penetration: PenetrationAlgorithm::default(),
         }
     }
 
-    pub fn compute(&mut self) {
+    /// Every contact between `a` and `b`: one per pair of touching children for compound
+    /// colliders, at most one otherwise.
+    pub fn compute_all(
+        a: &'a RigidBody2D,
+        b: &'a RigidBody2D,
+    ) -> Result<Vec<Self>, CollisionError> {
+        Self::compute_all_with(a, b, PenetrationAlgorithm::default())
+    }
+
+    /// `compute_all` with the given penetration algorithm.
+    pub fn compute_all_with(
+        a: &'a RigidBody2D,
+        b: &'a RigidBody2D,
+        penetration: PenetrationAlgorithm,
+    ) -> Result<Vec<Self>, CollisionError> {
+        let is_compound = |body: &RigidBody2D| matches!(body.collider, CompoundCollider { .. });
+
+        if !is_compound(a) && !is_compound(b) {
+            let mut manifold = Self::new(a, b);
+            manifold.penetration = penetration;
+            manifold.compute()?;
+
+            return Ok(match manifold.normal {
+                Some(_) => vec![manifold],
+                None => vec![],
+            });
+        }
+
+        let children_a = a.child_bodies();
+        let children_b = b.child_bodies();
+        let mut res = vec![];
+
+        for (index_a, child_a) in &children_a {
+            let aabb_a = child_a.world_aabb();
+
+            for (index_b, child_b) in &children_b {
+                if !aabb_a.overlaps(&child_b.world_aabb()) {
+                    continue;
+                }
+
+                let mut child_manifold = ContactManifold::new(child_a, child_b);
+                child_manifold.penetration = penetration;
+                child_manifold.compute()?;
+
+                if child_manifold.normal.is_some() {
+                    res.push(Self {
+                        a,
+                        b,
+                        child_a: *index_a,
+                        child_b: *index_b,
+                        normal: child_manifold.normal,
+                        points: child_manifold.points,
+                        penetration,
+                    });
+                }
+            }
+        }
+
+        Ok(res)
+    }
+
+    /// For compound colliders, only the contact of the deepest pair of children is kept,
+    /// see `compute_all` for every one of them.
+    ///
+    /// Fails when the shapes can't be collided, e.g. a body with a NaN position: the pair is left
+    /// untouched, for the caller to skip it or to stop.
+    pub fn compute(&mut self) -> Result<(), CollisionError> {
         match (&self.a.collider, &self.b.collider) {
+            (CompoundCollider { .. }, _) | (_, CompoundCollider { .. }) => {
+                let max_depth = |manifold: &Self| {
+                    manifold
+                        .points
+                        .iter()
+                        .map(|contact| contact.depth)
+                        .fold(f64::NEG_INFINITY, f64::max)
+                };
+
+                let deepest = Self::compute_all_with(self.a, self.b, self.penetration)?
+                    .into_iter()
+                    .max_by(|m1, m2| max_depth(m1).total_cmp(&max_depth(m2)));
+
+                match deepest {
+                    Some(manifold) => *self = manifold,
+                    None => self.unset_colliding(),
+                }
+
+                Ok(())
+            }
+            // Segments, chains and heightfields are meant for static bodies, which don't collide
+            // together.
+            (
+                SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. },
+                SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. },
+            ) => {
+                self.unset_colliding();
+                Ok(())
+            }
+            (SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. }, _)
+            | (_, SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. }) => {
+                chain_v_shape(self)
+            }
             (CircleCollider { .. }, CircleCollider { .. }) => sphere_v_sphere(self),
             (CircleCollider { .. }, PolygonCollider { .. })
             | (PolygonCollider { .. }, CircleCollider { .. }) => sphere_v_polygon(self),
             (PolygonCollider { .. }, PolygonCollider { .. }) => polygon_v_polygon(self),
+            (CapsuleCollider { .. }, CircleCollider { .. })
+            | (CircleCollider { .. }, CapsuleCollider { .. }) => capsule_v_sphere(self),
+            (CapsuleCollider { .. }, PolygonCollider { .. })
+            | (PolygonCollider { .. }, CapsuleCollider { .. }) => capsule_v_polygon(self),
+            (CapsuleCollider { .. }, CapsuleCollider { .. }) => capsule_v_capsule(self),
+            // Ellipses, user-defined shapes, and any pair without its own routine
+            _ => gjk_v_shape(self),
         }
     }
 
@@ -44,7 +230,243 @@ impl<'a> ContactManifold<'a> {
         self.normal = None;
         self.points.clear();
     }
-    pub fn add_point(&mut self, point: Vec2D, depth: f64) {
-        self.points.push((point, depth))
+    pub fn add_point(&mut self, point: Vec2D, depth: f64, body: ContactBody) {
+        self.points.push(ContactPoint {
+            point,
+            depth,
+            body,
+            feature: None,
+        })
+    }
+
+    /// `add_point`, for a contact coming from `feature`.
+    pub fn add_feature_point(
+        &mut self,
+        point: Vec2D,
+        depth: f64,
+        body: ContactBody,
+        feature: ContactFeature,
+    ) {
+        self.points.push(ContactPoint {
+            point,
+            depth,
+            body,
+            feature: Some(feature),
+        })
+    }
+
+    /// Sets the contact found by the algorithms shared between several pairs, whose normal goes
+    /// from `a` to `b` and whose points are half way between the surfaces: they are moved onto the
+    /// surface of `b`.
+    pub fn set_midway_contact(&mut self, contact: Option<Contact>) {
+        match contact {
+            None => self.unset_colliding(),
+            Some((normal, points)) => {
+                self.set_colliding(normal);
+                for (point, depth) in points {
+                    self.add_point(point - normal * (depth / 2.), depth, ContactBody::B);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::{Collider, CompoundChild},
+            manifold::ContactManifold,
+            support_map::SupportMap,
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[derive(Debug)]
+    struct Square {
+        half_side: f64,
+    }
+
+    impl SupportMap for Square {
+        fn support(&self, direction: Vec2D) -> Vec2D {
+            Vec2D::new(direction.x.signum(), direction.y.signum()) * self.half_side
+        }
+    }
+
+    /// Whether `point` is on the surface of `body`, which `outward` leaves.
+    fn on_surface(body: &RigidBody2D, point: Vec2D, outward: Vec2D) -> bool {
+        // GJK, behind `contains_point` for convex shapes, counts points a few hundredths away as
+        // touching.
+        let tolerance = 5e-2;
+
+        match body.collider {
+            // Lines along the x axis
+            Collider::SegmentCollider { .. }
+            | Collider::ChainCollider { .. }
+            | Collider::HeightfieldCollider { .. } => point.y.abs() < tolerance,
+            _ => {
+                let contains = |p: Vec2D| {
+                    body.collider
+                        .contains_point(body.to_local(p - body.position))
+                };
+                contains(point - outward * tolerance) && !contains(point + outward * tolerance)
+            }
+        }
+    }
+
+    #[test]
+    fn example_one() {
+        // A table: a top and two legs, standing on the ground
+        let table = Collider::compound(vec![
+            CompoundChild::new(Collider::rectangle(4., 0.5), Vec2D::new(0., 2.), 0.),
+            CompoundChild::new(Collider::rectangle(0.5, 2.), Vec2D::new(-1.5, 0.75), 0.),
+            CompoundChild::new(Collider::rectangle(0.5, 2.), Vec2D::new(1.5, 0.75), 0.),
+        ])
+        .unwrap();
+        let a = RigidBody2D::new(Vec2D::new(0., 0.1), table, 1.);
+        let b = RigidBody2D::new(Vec2D::new(0., -1.), Collider::rectangle(10., 2.), 0.);
+
+        let manifolds = ContactManifold::compute_all(&a, &b).unwrap();
+
+        assert_eq!(manifolds.len(), 2);
+        assert_eq!(
+            manifolds
+                .iter()
+                .map(|m| (m.child_a, m.child_b))
+                .collect::<Vec<_>>(),
+            vec![(Some(1), None), (Some(2), None)]
+        );
+
+        for manifold in &manifolds {
+            let normal = manifold.normal.unwrap();
+            assert!((normal * Vec2D::new(0., 1.)).abs() > 1. - 1e-9);
+            assert_eq!(manifold.points.len(), 2);
+        }
+
+        // Out of reach of the top
+        let c = RigidBody2D::new(Vec2D::new(0., 3.5), Collider::circle(1.), 1.);
+        assert!(ContactManifold::compute_all(&a, &c).unwrap().is_empty());
+
+        let c = RigidBody2D::new(Vec2D::new(0.5, 2.9), Collider::circle(1.), 1.);
+        let mut manifold = ContactManifold::new(&c, &a);
+        manifold.compute().unwrap();
+
+        assert!(manifold.normal.is_some());
+        assert_eq!((manifold.child_a, manifold.child_b), (None, Some(0)));
+    }
+
+    /// Every pair of shapes, in both orders, follows the conventions of the manifold.
+    #[test]
+    fn example_two() {
+        // With the distance from their position to their top and bottom
+        let shapes = vec![
+            ("circle", Collider::circle(1.), 1.),
+            ("box", Collider::rectangle(2., 2.), 1.),
+            (
+                "rounded box",
+                Collider::rounded_rectangle(1.8, 1.8, 0.1),
+                1.,
+            ),
+            ("capsule", Collider::capsule(1., 0.5), 0.5),
+            ("ellipse", Collider::ellipse(1.5, 0.75), 0.75),
+            ("convex", Collider::convex(Square { half_side: 0.8 }), 0.8),
+            (
+                "compound",
+                Collider::compound(vec![
+                    CompoundChild::new(Collider::rectangle(1., 2.), Vec2D::new(-0.5, 0.), 0.),
+                    CompoundChild::new(Collider::rectangle(1., 2.), Vec2D::new(0.5, 0.), 0.),
+                ])
+                .unwrap(),
+                1.,
+            ),
+        ];
+        // Lying on the x axis
+        let grounds = vec![
+            (
+                "segment",
+                Collider::segment(Vec2D::new(-3., 0.), Vec2D::new(3., 0.)),
+                Vec2D::zero(),
+            ),
+            (
+                "chain",
+                Collider::chain(&[Vec2D::new(-3., 0.), Vec2D::new(0., 0.), Vec2D::new(3., 0.)])
+                    .unwrap(),
+                Vec2D::zero(),
+            ),
+            (
+                "heightfield",
+                Collider::heightfield(&[0.; 7], 1.).unwrap(),
+                Vec2D::new(-3., 0.),
+            ),
+        ];
+
+        // The upper shape sinks 0.2 into the lower one.
+        let mut pairs = vec![];
+        for (name_a, collider_a, half_a) in &shapes {
+            for (name_b, collider_b, half_b) in &shapes {
+                let lower = RigidBody2D::new(Vec2D::zero(), collider_a.clone(), 1.);
+                let upper = RigidBody2D::new(
+                    Vec2D::new(0.1, half_a + half_b - 0.2),
+                    collider_b.clone(),
+                    1.,
+                );
+                pairs.push((format!("{name_a} under {name_b}"), lower, upper));
+            }
+        }
+        for (name_a, collider_a, position) in &grounds {
+            for (name_b, collider_b, half_b) in &shapes {
+                let lower = RigidBody2D::new(*position, collider_a.clone(), 0.);
+                let upper = RigidBody2D::new(Vec2D::new(0.1, half_b - 0.2), collider_b.clone(), 1.);
+                pairs.push((format!("{name_a} under {name_b}"), lower, upper));
+            }
+        }
+
+        for (name, lower, upper) in &pairs {
+            for (a, b) in [(lower, upper), (upper, lower)] {
+                let mut manifold = ContactManifold::new(a, b);
+                manifold.compute().unwrap();
+
+                let normal = manifold
+                    .normal
+                    .unwrap_or_else(|| panic!("{name}: no contact"));
+                assert!((normal.norm() - 1.).abs() < 1e-9, "{name}: {normal}");
+                assert!(normal * (b.position - a.position) > 0., "{name}: {normal}");
+                assert!(!manifold.points.is_empty(), "{name}");
+
+                for contact in &manifold.points {
+                    assert!(
+                        contact.depth > 0. && contact.depth < 0.2 + 1e-2,
+                        "{name}: {contact:?}"
+                    );
+                    assert!(
+                        on_surface(a, contact.on_a(normal), normal),
+                        "{name}: {contact:?} isn't on a"
+                    );
+                    assert!(
+                        on_surface(b, contact.on_b(normal), -normal),
+                        "{name}: {contact:?} isn't on b"
+                    );
+                }
+
+                // The same contact, the other way around
+                let mut reversed = ContactManifold::new(b, a);
+                reversed.compute().unwrap();
+
+                let reversed_normal = reversed.normal.unwrap();
+                assert!((reversed_normal + normal).norm() < 1e-2, "{name}");
+
+                for contact in &reversed.points {
+                    let on_b = contact.on_a(reversed_normal);
+                    assert!(
+                        manifold.points.iter().any(|other| {
+                            (other.depth - contact.depth).abs() < 1e-2
+                                && (other.on_b(normal) - on_b).norm() < 2e-2
+                        }),
+                        "{name}: {contact:?}"
+                    );
+                }
+            }
+        }
     }
 }
@@ -1,3 +1,8 @@
+pub mod capsule_v_capsule;
+pub mod capsule_v_polygon;
+pub mod capsule_v_sphere;
+pub mod chain_v_shape;
+pub mod gjk_v_shape;
 pub mod polygon_v_polygon;
 pub mod sphere_v_polygon;
 pub mod sphere_v_sphere;
@@ -0,0 +1,102 @@
+use crate::collision::{
+    algorithms::{clipping::collide_rounded_polygons, segment::capsule_segment},
+    error::CollisionError,
+    manifold::ContactManifold,
+};
+
+/// Both capsules are seen as polygons made of only two vertices, rounded by their radius.
+pub fn capsule_v_capsule(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
+    let (a1, a2, a_radius) = capsule_segment(manifold.a)?;
+    let (b1, b2, b_radius) = capsule_segment(manifold.b)?;
+
+    manifold.set_midway_contact(collide_rounded_polygons(
+        &[a1, a2],
+        a_radius,
+        &[b1, b2],
+        b_radius,
+    ));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::Collider,
+            manifold::{ContactBody, ContactManifold, ContactPoint},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // Parallel and overlapping: two points where the segments overlap
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::capsule(2., 1.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(3., 1.5), Collider::capsule(2., 1.), 1.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        assert_eq!(
+            manifold.points,
+            vec![
+                ContactPoint {
+                    point: Vec2D::new(2., 0.5),
+                    depth: 0.5,
+                    body: ContactBody::B,
+                    feature: None
+                },
+                ContactPoint {
+                    point: Vec2D::new(1., 0.5),
+                    depth: 0.5,
+                    body: ContactBody::B,
+                    feature: None
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn example_two() {
+        // Crossed: one point
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::capsule(2., 1.), 1.);
+        let mut b = RigidBody2D::new(Vec2D::new(1., 3.5), Collider::capsule(2., 1.), 1.);
+        b.angle = std::f64::consts::FRAC_PI_2;
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., 1.)).near_zero());
+        assert_eq!(manifold.points.len(), 1);
+        assert!((manifold.points[0].point - Vec2D::new(1., 0.5)).near_zero());
+        assert!((manifold.points[0].depth - 0.5).abs() < 1e-9);
+        assert_eq!(manifold.points[0].body, ContactBody::B);
+
+        // End to end
+        b.position = Vec2D::new(4.5, 0.);
+        b.angle = 0.;
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(1., 0.)));
+        assert_eq!(
+            manifold.points,
+            vec![ContactPoint {
+                point: Vec2D::new(1.5, 0.),
+                depth: 1.5,
+                body: ContactBody::B,
+                feature: None
+            }]
+        );
+
+        b.position = Vec2D::new(6.5, 0.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::{
+    collision::{
+        algorithms::{clipping::collide_rounded_polygons, segment::capsule_segment},
+        collider::Collider::{CapsuleCollider, PolygonCollider},
+        error::CollisionError,
+        manifold::ContactManifold,
+    },
+    linalg::Vec2D,
+    rigidbody2d::RigidBody2D,
+};
+
+/// The world vertices of the polygon, and its radius.
+fn polygon_to_global(body: &RigidBody2D) -> Result<(Vec<Vec2D>, f64), CollisionError> {
+    match &body.collider {
+        PolygonCollider { vertices, radius } => Ok((
+            vertices.iter().map(|v| body.to_global(*v)).collect(),
+            *radius,
+        )),
+        _ => Err(CollisionError::UnsupportedPair),
+    }
+}
+
+/// The capsule is seen as a polygon made of only two vertices, rounded by its radius.
+pub fn capsule_v_polygon(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
+    let result = match (&manifold.a.collider, &manifold.b.collider) {
+        (CapsuleCollider { .. }, PolygonCollider { .. }) => {
+            let (p1, p2, radius) = capsule_segment(manifold.a)?;
+            let (vertices, polygon_radius) = polygon_to_global(manifold.b)?;
+            collide_rounded_polygons(&[p1, p2], radius, &vertices, polygon_radius)
+        }
+        (PolygonCollider { .. }, CapsuleCollider { .. }) => {
+            let (p1, p2, radius) = capsule_segment(manifold.b)?;
+            let (vertices, polygon_radius) = polygon_to_global(manifold.a)?;
+            collide_rounded_polygons(&vertices, polygon_radius, &[p1, p2], radius)
+        }
+        _ => return Err(CollisionError::UnsupportedPair),
+    };
+
+    manifold.set_midway_contact(result);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::Collider,
+            manifold::{ContactBody, ContactManifold},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // Lying on a box
+        let a = RigidBody2D::new(Vec2D::new(0., 1.4), Collider::capsule(1., 0.5), 1.);
+        let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(4., 2.), 1.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., -1.)));
+        assert_eq!(manifold.points.len(), 2);
+        for contact in &manifold.points {
+            assert!((contact.depth - 0.1).abs() < 1e-9);
+            assert!((contact.point.y - 1.).abs() < 1e-9);
+            assert_eq!(contact.body, ContactBody::B);
+        }
+
+        let mut manifold = ContactManifold::new(&b, &a);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        assert_eq!(manifold.points.len(), 2);
+        for contact in &manifold.points {
+            assert!((contact.point.y - 0.9).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn example_two() {
+        // Standing on a box
+        let mut a = RigidBody2D::new(Vec2D::new(0.5, 2.4), Collider::capsule(1., 0.5), 1.);
+        a.angle = std::f64::consts::FRAC_PI_2;
+        let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(4., 2.), 1.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., -1.)).near_zero());
+        assert_eq!(manifold.points.len(), 1);
+        assert!((manifold.points[0].point - Vec2D::new(0.5, 1.)).near_zero());
+        assert!((manifold.points[0].depth - 0.1).abs() < 1e-9);
+
+        a.position = Vec2D::new(0.5, 2.6);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+}
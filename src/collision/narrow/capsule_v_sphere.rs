@@ -0,0 +1,118 @@
+use crate::collision::{
+    algorithms::segment::{capsule_segment, closest_point_on_segment},
+    collider::Collider::{CapsuleCollider, CircleCollider},
+    error::CollisionError,
+    manifold::{ContactBody, ContactManifold},
+};
+
+pub fn capsule_v_sphere(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
+    let (capsule, circle, flipped) = match (&manifold.a.collider, &manifold.b.collider) {
+        (CapsuleCollider { .. }, CircleCollider { .. }) => (manifold.a, manifold.b, false),
+        (CircleCollider { .. }, CapsuleCollider { .. }) => (manifold.b, manifold.a, true),
+        _ => return Err(CollisionError::UnsupportedPair),
+    };
+
+    let circle_radius = match circle.collider {
+        CircleCollider { radius } => radius,
+        _ => unreachable!(),
+    };
+
+    let (p1, p2, capsule_radius) = capsule_segment(capsule)?;
+    let (closest, _) = closest_point_on_segment(circle.position, p1, p2);
+
+    let l = circle.position - closest;
+    let distance = l.norm();
+
+    if distance > capsule_radius + circle_radius {
+        manifold.unset_colliding();
+        return Ok(());
+    }
+
+    // From the capsule to the circle
+    let normal = if distance < f64::EPSILON {
+        // The center of the circle is on the segment: push it out of the flat side.
+        (p2 - p1).left().normalize()
+    } else {
+        l / distance
+    };
+
+    let depth = capsule_radius + circle_radius - distance;
+    let surface_circle = circle.position - normal * circle_radius;
+
+    if flipped {
+        manifold.set_colliding(-normal);
+        manifold.add_point(surface_circle, depth, ContactBody::A);
+    } else {
+        manifold.set_colliding(normal);
+        manifold.add_point(surface_circle, depth, ContactBody::B);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::Collider,
+            manifold::{ContactBody, ContactManifold, ContactPoint},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::capsule(2., 1.), 1.);
+        let b = RigidBody2D::new(Vec2D::new(1., 1.5), Collider::circle(1.), 1.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        assert_eq!(
+            manifold.points,
+            vec![ContactPoint {
+                point: Vec2D::new(1., 0.5),
+                depth: 0.5,
+                body: ContactBody::B,
+                feature: None
+            }]
+        );
+
+        let mut manifold = ContactManifold::new(&b, &a);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., -1.)));
+        assert_eq!(
+            manifold.points,
+            vec![ContactPoint {
+                point: Vec2D::new(1., 0.5),
+                depth: 0.5,
+                body: ContactBody::A,
+                feature: None
+            }]
+        );
+    }
+
+    #[test]
+    fn example_two() {
+        let mut a = RigidBody2D::new(Vec2D::zero(), Collider::capsule(2., 1.), 1.);
+        a.angle = std::f64::consts::FRAC_PI_2;
+
+        // Near the rounded end, now at the top
+        let b = RigidBody2D::new(Vec2D::new(0., 4.), Collider::circle(1.5), 1.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., 1.)).near_zero());
+        assert!((manifold.points[0].depth - 0.5).abs() < 1e-9);
+
+        let b = RigidBody2D::new(Vec2D::new(3., 0.), Collider::circle(1.5), 1.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+}
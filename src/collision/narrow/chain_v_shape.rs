@@ -0,0 +1,376 @@
+use crate::{
+    collision::{
+        algorithms::{
+            clipping::collide_rounded_polygons,
+            segment::{
+                capsule_segment, chain_segments, closest_point_on_segment, heightfield_segments,
+                ChainSegment,
+            },
+        },
+        collider::Collider::{
+            CapsuleCollider, ChainCollider, CircleCollider, HeightfieldCollider, PolygonCollider,
+            SegmentCollider,
+        },
+        error::CollisionError,
+        manifold::{Contact, ContactManifold, PenetrationAlgorithm},
+        narrow::gjk_v_shape::collide_convex,
+        support_map::SupportMap,
+    },
+    linalg::{Vec2D, TOLERANCE},
+    rigidbody2d::RigidBody2D,
+};
+
+fn collide_segment(
+    segment: &ChainSegment,
+    shape: &RigidBody2D,
+    penetration: PenetrationAlgorithm,
+) -> Result<Option<Contact>, CollisionError> {
+    match &shape.collider {
+        CircleCollider { radius } => {
+            let (closest, _) = closest_point_on_segment(shape.position, segment.start, segment.end);
+
+            let l = shape.position - closest;
+            let distance = l.norm();
+
+            if distance > *radius {
+                return Ok(None);
+            }
+
+            let normal = if distance < f64::EPSILON {
+                (segment.end - segment.start).left().normalize()
+            } else {
+                l / distance
+            };
+            let surface = shape.position - normal * *radius;
+
+            Ok(Some((
+                normal,
+                vec![((closest + surface) / 2., radius - distance)],
+            )))
+        }
+        PolygonCollider { vertices, radius } => {
+            let vertices: Vec<Vec2D> = vertices.iter().map(|v| shape.to_global(*v)).collect();
+            Ok(collide_rounded_polygons(
+                &[segment.start, segment.end],
+                0.,
+                &vertices,
+                *radius,
+            ))
+        }
+        CapsuleCollider { .. } => {
+            let (p1, p2, radius) = capsule_segment(shape)?;
+            Ok(collide_rounded_polygons(
+                &[segment.start, segment.end],
+                0.,
+                &[p1, p2],
+                radius,
+            ))
+        }
+        // Through the support functions, for the shapes without edges
+        _ => {
+            let body = RigidBody2D::new(
+                Vec2D::zero(),
+                SegmentCollider {
+                    start: segment.start,
+                    end: segment.end,
+                },
+                0.,
+            );
+            collide_convex(&body, shape, penetration)
+        }
+    }
+}
+
+/// Whether a contact with `segment` at `surface`, a point on the segment, belongs to a neighbour.
+///
+/// A shape sliding across a joint touches the vertex of the segment it comes from with a normal
+/// leaning toward the next one, which would make it catch. When the normal leans toward the ghost
+/// vertex, the next segment already handles the contact with its own face.
+fn is_ghost_contact(segment: &ChainSegment, normal: Vec2D, surface: Vec2D) -> bool {
+    let edge = (segment.end - segment.start).normalize();
+
+    // Contacts on the face of the segment always belong to it.
+    if (normal * edge).abs() < TOLERANCE {
+        return false;
+    }
+
+    let (vertex, ghost) = if (surface - segment.start).norm() < TOLERANCE {
+        (segment.start, segment.ghost_1)
+    } else if (surface - segment.end).norm() < TOLERANCE {
+        (segment.end, segment.ghost_2)
+    } else {
+        return false;
+    };
+
+    match ghost {
+        Some(ghost) => normal * (ghost - vertex).normalize() > TOLERANCE,
+        None => false,
+    }
+}
+
+/// The contact of `shape` with the face of `segment` on the side `normal` points to, when every
+/// contact found belongs to a neighbour. With the shape resting right on a joint, the segments on
+/// each side push it toward the other and none would keep the contact.
+fn face_contact(segment: &ChainSegment, shape: &RigidBody2D, normal: Vec2D) -> Option<Contact> {
+    let mut face = (segment.end - segment.start).left().normalize();
+    if face * normal < 0. {
+        face = -face;
+    }
+
+    let deepest = shape.support(-face);
+    let depth = (segment.start - deepest) * face;
+
+    if depth <= 0. {
+        return None;
+    }
+
+    Some((face, vec![(deepest + face * (depth / 2.), depth)]))
+}
+
+/// Collides each segment of a segment, chain or heightfield collider with the other shape, a
+/// circle, polygon, capsule or any convex shape. The deepest contact gives the normal, and the
+/// contacts of the other segments sharing it add their points.
+/// Heightfields only collide the cells under the bounding box of the shape.
+pub fn chain_v_shape(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
+    let (chain, shape, flipped) = match (&manifold.a.collider, &manifold.b.collider) {
+        (SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. }, _) => {
+            (manifold.a, manifold.b, false)
+        }
+        (_, SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. }) => {
+            (manifold.b, manifold.a, true)
+        }
+        _ => return Err(CollisionError::UnsupportedPair),
+    };
+
+    let mut contacts: Vec<Contact> = vec![];
+
+    let segments = match &chain.collider {
+        HeightfieldCollider { .. } => heightfield_segments(chain, &shape.world_aabb())?,
+        _ => chain_segments(chain)?,
+    };
+
+    for segment in segments {
+        if let Some((normal, points)) = collide_segment(&segment, shape, manifold.penetration)? {
+            // The points are half way between the surfaces.
+            let kept: Vec<(Vec2D, f64)> = points
+                .iter()
+                .copied()
+                .filter(|(point, depth)| {
+                    !is_ghost_contact(&segment, normal, *point + normal * (depth / 2.))
+                })
+                .collect();
+
+            if !kept.is_empty() {
+                contacts.push((normal, kept));
+            } else if let Some(contact) = face_contact(&segment, shape, normal) {
+                contacts.push(contact);
+            }
+        }
+    }
+
+    let max_depth = |points: &Vec<(Vec2D, f64)>| {
+        points
+            .iter()
+            .map(|(_, depth)| *depth)
+            .fold(f64::NEG_INFINITY, f64::max)
+    };
+
+    let normal = match contacts
+        .iter()
+        .max_by(|(_, a), (_, b)| max_depth(a).total_cmp(&max_depth(b)))
+    {
+        Some((normal, _)) => *normal,
+        None => {
+            manifold.unset_colliding();
+            return Ok(());
+        }
+    };
+
+    let mut points: Vec<(Vec2D, f64)> = vec![];
+
+    for (other_normal, other_points) in &contacts {
+        if *other_normal * normal < 1. - TOLERANCE {
+            continue;
+        }
+
+        for (point, depth) in other_points {
+            // Neighbouring segments share their vertices.
+            let duplicate = points.iter().any(|(p, _)| (*p - *point).norm() < TOLERANCE);
+
+            if !duplicate {
+                points.push((*point, *depth));
+            }
+        }
+    }
+
+    // A face lying across several segments only needs its two ends.
+    if points.len() > 2 {
+        let tangent = normal.right();
+        let along = |(point, _): &&(Vec2D, f64)| *point * tangent;
+
+        let first = *points
+            .iter()
+            .min_by(|p, q| along(p).total_cmp(&along(q)))
+            .unwrap();
+        let last = *points
+            .iter()
+            .max_by(|p, q| along(p).total_cmp(&along(q)))
+            .unwrap();
+
+        points = vec![first, last];
+    }
+
+    manifold.set_midway_contact(Some((if flipped { -normal } else { normal }, points)));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            algorithms::segment::{heightfield_segments, ChainSegment},
+            collider::Collider,
+            manifold::{ContactBody, ContactManifold},
+            narrow::chain_v_shape::is_ghost_contact,
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    fn flat_ground() -> RigidBody2D {
+        RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::chain(&[Vec2D::new(-4., 0.), Vec2D::new(0., 0.), Vec2D::new(4., 0.)])
+                .unwrap(),
+            0.,
+        )
+    }
+
+    #[test]
+    fn example_one() {
+        // A box sliding across the joint, slightly past it
+        let ground = flat_ground();
+        let b = RigidBody2D::new(Vec2D::new(-0.995, 0.99), Collider::rectangle(2., 2.), 1.);
+
+        let mut manifold = ContactManifold::new(&ground, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        assert_eq!(manifold.points.len(), 2);
+        for contact in &manifold.points {
+            assert!((contact.depth - 0.01).abs() < 1e-9);
+            assert_eq!(contact.body, ContactBody::B);
+        }
+
+        let mut manifold = ContactManifold::new(&b, &ground);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., -1.)));
+        assert_eq!(manifold.points.len(), 2);
+    }
+
+    #[test]
+    fn example_two() {
+        // A circle rolling over the joint only touches the face under it.
+        let ground = flat_ground();
+        let b = RigidBody2D::new(Vec2D::new(0.1, 0.9), Collider::circle(1.), 1.);
+
+        let mut manifold = ContactManifold::new(&ground, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        assert_eq!(manifold.points.len(), 1);
+        assert!((manifold.points[0].point - Vec2D::new(0.1, -0.1)).near_zero());
+        assert!((manifold.points[0].depth - 0.1).abs() < 1e-9);
+
+        // A lone segment, from below
+        let segment = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::segment(Vec2D::new(-1., 0.), Vec2D::new(1., 0.)),
+            0.,
+        );
+        let b = RigidBody2D::new(Vec2D::new(0., -0.5), Collider::capsule(1., 1.), 1.);
+
+        let mut manifold = ContactManifold::new(&segment, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., -1.)).near_zero());
+        assert_eq!(manifold.points.len(), 2);
+
+        let b = RigidBody2D::new(Vec2D::new(0., -1.5), Collider::capsule(1., 1.), 1.);
+        let mut manifold = ContactManifold::new(&segment, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+
+    #[test]
+    fn example_three() {
+        let segment = ChainSegment {
+            ghost_1: Some(Vec2D::new(-4., 0.)),
+            start: Vec2D::new(0., 0.),
+            end: Vec2D::new(4., 0.),
+            ghost_2: Some(Vec2D::new(8., -1.)),
+        };
+
+        // Pushed back toward the previous segment, on the vertex they share
+        assert!(is_ghost_contact(
+            &segment,
+            Vec2D::new(-1., 0.),
+            Vec2D::new(0., 0.)
+        ));
+        // On the face
+        assert!(!is_ghost_contact(
+            &segment,
+            Vec2D::new(0., 1.),
+            Vec2D::new(0., 0.)
+        ));
+        // Around a convex corner, both segments may have it.
+        assert!(!is_ghost_contact(
+            &segment,
+            Vec2D::new(1., 8.).normalize(),
+            Vec2D::new(4., 0.)
+        ));
+        // Not on a vertex
+        assert!(!is_ghost_contact(
+            &segment,
+            Vec2D::new(-1., 0.),
+            Vec2D::new(2., 0.)
+        ));
+    }
+
+    #[test]
+    fn example_four() {
+        // A long flat terrain, of which only the cells under the box are collided
+        let terrain = RigidBody2D::new(
+            Vec2D::new(-1000., 0.),
+            Collider::heightfield(&[0.; 2000], 1.).unwrap(),
+            0.,
+        );
+        let b = RigidBody2D::new(Vec2D::new(0.5, 0.99), Collider::rectangle(2., 2.), 1.);
+
+        assert_eq!(
+            heightfield_segments(&terrain, &b.world_aabb())
+                .unwrap()
+                .len(),
+            3
+        );
+
+        let mut manifold = ContactManifold::new(&b, &terrain);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., -1.)));
+        assert_eq!(manifold.points.len(), 2);
+        for contact in &manifold.points {
+            assert!((contact.depth - 0.01).abs() < 1e-9);
+        }
+
+        let b = RigidBody2D::new(Vec2D::new(0.5, 1.5), Collider::circle(1.), 1.);
+        let mut manifold = ContactManifold::new(&terrain, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+}
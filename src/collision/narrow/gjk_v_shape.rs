@@ -0,0 +1,121 @@
+use crate::{
+    collision::{
+        algorithms::{epa::epa, gjk::gjk_collision, mpr::mpr},
+        error::CollisionError,
+        manifold::{Contact, ContactManifold, PenetrationAlgorithm},
+    },
+    rigidbody2d::RigidBody2D,
+};
+
+/// Contact between two convex shapes through their support functions only, for the ones without
+/// edges to clip: GJK tells whether they overlap, then EPA gives the normal, going from `a` to
+/// `b`, and the depth, or MPR does both. The single contact point is half way between the deepest
+/// points of each shape.
+pub fn collide_convex(
+    a: &RigidBody2D,
+    b: &RigidBody2D,
+    penetration: PenetrationAlgorithm,
+) -> Result<Option<Contact>, CollisionError> {
+    let result = match penetration {
+        PenetrationAlgorithm::Epa => match gjk_collision(a, b)? {
+            Some(simplex) => epa(simplex, a, b)?,
+            None => return Ok(None),
+        },
+        PenetrationAlgorithm::Mpr => match mpr(a, b)? {
+            Some(result) => result,
+            None => return Ok(None),
+        },
+    };
+
+    // Both push `a` out of `b`.
+    Ok(Some((
+        -result.normal,
+        vec![((result.point_a + result.point_b) / 2., result.depth)],
+    )))
+}
+
+pub fn gjk_v_shape(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
+    manifold.set_midway_contact(collide_convex(
+        manifold.a,
+        manifold.b,
+        manifold.penetration,
+    )?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::Collider,
+            manifold::{ContactManifold, PenetrationAlgorithm},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // An ellipse lying on a box
+        let a = RigidBody2D::new(Vec2D::new(0., 1.4), Collider::ellipse(2., 0.5), 1.);
+        let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(6., 2.), 1.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., -1.)).near_zero());
+        assert_eq!(manifold.points.len(), 1);
+
+        // EPA converges slowly along the curve of the ellipse.
+        let contact = manifold.points[0];
+        assert!(contact.point.x.abs() < 1e-2 && (contact.point.y - 1.).abs() < 1e-3);
+        assert!((contact.depth - 0.1).abs() < 1e-3);
+
+        // Tilted and resting on a ground made of segments
+        let mut a = RigidBody2D::new(Vec2D::new(0., 1.), Collider::ellipse(2., 0.5), 1.);
+        a.angle = std::f64::consts::FRAC_PI_2;
+        let ground = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::chain(&[Vec2D::new(-4., 0.), Vec2D::new(0., 0.), Vec2D::new(4., 0.)])
+                .unwrap(),
+            0.,
+        );
+
+        let mut manifold = ContactManifold::new(&ground, &a);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., 1.)).near_zero());
+        assert!((manifold.points[0].depth - 1.).abs() < 1e-3);
+
+        a.position = Vec2D::new(0., 2.1);
+        let mut manifold = ContactManifold::new(&ground, &a);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+
+    #[test]
+    fn example_two() {
+        // The ellipse lying on the box, through MPR
+        let a = RigidBody2D::new(Vec2D::new(0., 1.4), Collider::ellipse(2., 0.5), 1.);
+        let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(6., 2.), 1.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.penetration = PenetrationAlgorithm::Mpr;
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., -1.)).near_zero());
+        assert!((manifold.points[0].depth - 0.1).abs() < 1e-3);
+
+        let a = RigidBody2D::new(Vec2D::new(0., 1.6), Collider::ellipse(2., 0.5), 1.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.penetration = PenetrationAlgorithm::Mpr;
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+    }
+}
@@ -4,18 +4,22 @@
 
 use crate::{
     collision::{
-        algorithms::sat::sat, collider::Collider::PolygonCollider, manifold::ContactManifold,
+        algorithms::{clipping::collide_rounded_polygons, sat::sat},
+        collider::Collider::PolygonCollider,
+        error::CollisionError,
+        manifold::{ContactBody, ContactManifold},
     },
     linalg::Vec2D,
     rigidbody2d::RigidBody2D,
 };
 
-fn vertices_to_global(body: &RigidBody2D) -> Vec<Vec2D> {
+fn vertices_to_global(body: &RigidBody2D) -> Result<Vec<Vec2D>, CollisionError> {
     match &body.collider {
         PolygonCollider {
             vertices: local_vertices,
-        } => local_vertices.iter().map(|v| body.to_global(*v)).collect(),
-        _ => panic!("Inappropriate fonction used for the narrow phase."),
+            ..
+        } => Ok(local_vertices.iter().map(|v| body.to_global(*v)).collect()),
+        _ => Err(CollisionError::UnsupportedPair),
     }
 }
 
@@ -108,7 +112,6 @@ impl EdgeFeature {
 }
 
 fn get_farthest_edge(vertices: &Vec<Vec2D>, dir: Vec2D) -> EdgeFeature {
-    println!("Getting the farthest with dir: {dir}");
     let n = vertices.len();
     let index = get_farthest_vertex_index(vertices, dir);
 
@@ -116,28 +119,18 @@ fn get_farthest_edge(vertices: &Vec<Vec2D>, dir: Vec2D) -> EdgeFeature {
     let prev_vertex = vertices[(index + n - 1) % n];
     let next_vertex = vertices[(index + 1) % n];
 
-    println!(
-        "The vertex the farthest is {index}: {vertex} (prev: {prev_vertex}, next: {next_vertex})"
-    );
-
+    // Inward normals, the polygons being counter-clockwise
     let left_normal = (vertex - prev_vertex).left().normalize();
     let right_normal = (next_vertex - vertex).left().normalize();
 
-    println!("Left_normal: {left_normal}, Right_normal: {right_normal}");
-
     if left_normal * dir < right_normal * dir {
-        println!("Choosed left");
-
         EdgeFeature::new(prev_vertex, vertex, vertex)
     } else {
-        println!("Choosed right");
-
         EdgeFeature::new(vertex, next_vertex, vertex)
     }
 }
 
 fn clip(p1: Vec2D, p2: Vec2D, n: Vec2D, offset: f64) -> Vec<Vec2D> {
-    println!("Clipping p1: {p1}, p2: {p2}, n: {n}, offset: {offset}");
     let mut points = Vec::with_capacity(3);
 
     // calculate the distance between the end points of the edge and the clip line
@@ -146,11 +139,9 @@ fn clip(p1: Vec2D, p2: Vec2D, n: Vec2D, offset: f64) -> Vec<Vec2D> {
 
     // add the points if they are behind the line
     if d1 <= 0.0 {
-        println!("We keep p1");
         points.push(p1);
     }
     if d2 <= 0.0 {
-        println!("We keep p2");
         points.push(p2);
     }
 
@@ -162,99 +153,107 @@ fn clip(p1: Vec2D, p2: Vec2D, n: Vec2D, offset: f64) -> Vec<Vec2D> {
         let u = d1 / (d1 - d2);
         e *= u;
         e += p1;
-        println!("We add a mid_point: {e}");
         points.push(e);
     }
     return points;
 }
 
-pub fn polygon_v_polygon(manifold: &mut ContactManifold) {
+fn radius(body: &RigidBody2D) -> Result<f64, CollisionError> {
+    match &body.collider {
+        PolygonCollider { radius, .. } => Ok(*radius),
+        _ => Err(CollisionError::UnsupportedPair),
+    }
+}
+
+pub fn polygon_v_polygon(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
     let (a_vertices, b_vertices) = (
-        vertices_to_global(manifold.a),
-        vertices_to_global(manifold.b),
+        vertices_to_global(manifold.a)?,
+        vertices_to_global(manifold.b)?,
     );
 
-    println!("Vertices: a: {:#?}\nb: {:#?}", a_vertices, b_vertices);
-    println!("=====================================================");
+    // Rounded polygons are collided through the distance between their cores.
+    let (a_radius, b_radius) = (radius(manifold.a)?, radius(manifold.b)?);
+    if a_radius > 0. || b_radius > 0. {
+        manifold.set_midway_contact(collide_rounded_polygons(
+            &a_vertices,
+            a_radius,
+            &b_vertices,
+            b_radius,
+        ));
+        return Ok(());
+    }
 
-    match sat(manifold.a, manifold.b) {
+    match sat(manifold.a, manifold.b)? {
         None => {
             manifold.unset_colliding();
         }
         Some(mtv) => {
-            println!("End of sat:\n\n");
             let normal = mtv.normalize();
 
             manifold.set_colliding(normal);
 
             let farthest_a = get_farthest_edge(&a_vertices, normal);
 
-            println!("Farthest on a: {:#?} ({})", farthest_a, farthest_a.to_vec());
-            println!("a dot n: {}", farthest_a.to_vec() * normal);
-
             let farthest_b = get_farthest_edge(&b_vertices, -normal);
 
-            println!("Farthest on b: {:#?} ({})", farthest_b, farthest_b.to_vec());
-            println!("b dot n: {}", farthest_b.to_vec() * normal);
-
             let (reference, incident, flipped) =
                 if (farthest_a.to_vec() * normal).abs() <= (farthest_b.to_vec() * normal).abs() {
                     (farthest_a, farthest_b, false)
                 } else {
-                    println!("Flipping");
                     (farthest_b, farthest_a, true)
                 };
 
-            println!(
-                "The reference edge: {}, the incident one: {}",
-                reference.to_vec(),
-                incident.to_vec()
-            );
-
             let refev = reference.to_vec().normalize();
             let offset_1 = -refev * reference.first;
 
-            println!("The first offset is {offset_1}");
-
             let cp = clip(incident.first, incident.second, -refev, offset_1);
 
+            // The incident edge is beside the reference one: no contact to report.
             if cp.len() < 2 {
-                return;
+                manifold.unset_colliding();
+                return Ok(());
             }
 
             let offset_2 = refev * reference.second;
 
             let cp = clip(cp[0], cp[1], refev, offset_2);
 
+            // Same on the other side
             if cp.len() < 2 {
-                return;
+                manifold.unset_colliding();
+                return Ok(());
             }
 
-            let front_normal = refev.left(); // sus but ok
+            // Inward, the polygons being counter-clockwise
+            let front_normal = refev.left();
             let front_offset = front_normal * reference.max;
 
-            println!("Front_normal: {front_normal}, front_offset: {front_offset}");
-
-            if flipped {
-                manifold.set_colliding(-normal);
-            }
+            // The clipped points are on the incident edge.
+            let body = if flipped {
+                ContactBody::A
+            } else {
+                ContactBody::B
+            };
 
             for point in cp {
                 let depth = front_normal * point - front_offset;
-                println!("Point: {point} has depth {depth}");
                 if depth >= 0. {
-                    println!("Keeping it");
-                    manifold.add_point(point, depth);
+                    manifold.add_point(point, depth, body);
                 }
             }
         }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        collision::{collider::Collider, manifold::ContactManifold},
+        collision::{
+            collider::Collider,
+            manifold::{ContactBody, ContactManifold, ContactPoint},
+        },
         linalg::Vec2D,
         rigidbody2d::RigidBody2D,
     };
@@ -267,17 +266,51 @@ mod tests {
         b.angle = std::f64::consts::FRAC_PI_4;
 
         let mut manifold = ContactManifold::new(&a, &b);
-        manifold.compute();
+        manifold.compute().unwrap();
 
         assert_eq!(
             manifold.points,
-            vec![(
-                Vec2D {
+            vec![ContactPoint {
+                point: Vec2D {
                     x: -1.1102230246251565e-16,
                     y: 0.5857864376269051
                 },
-                0.4142135623730949
-            )]
+                depth: 0.4142135623730949,
+                body: ContactBody::B,
+                feature: None
+            }]
+        );
+    }
+
+    #[test]
+    fn example_two() {
+        // Rounded boxes stacked with their cores 0.15 apart, less than the sum of their radii: they
+        // overlap by 0.05.
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::rounded_rectangle(2., 2., 0.1), 1.);
+        let b = RigidBody2D::new(
+            Vec2D::new(0.5, 2.15),
+            Collider::rounded_rectangle(2., 2., 0.1),
+            1.,
+        );
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        assert_eq!(manifold.points.len(), 2);
+        for contact in &manifold.points {
+            assert!((contact.depth - 0.05).abs() < 1e-9);
+            assert!((contact.point.y - 1.05).abs() < 1e-9);
+        }
+
+        let b = RigidBody2D::new(
+            Vec2D::new(0.5, 2.25),
+            Collider::rounded_rectangle(2., 2., 0.1),
+            1.,
         );
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
     }
 }
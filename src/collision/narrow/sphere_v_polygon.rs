@@ -1,113 +1,163 @@
+// Closest feature of the polygon to the center of the circle, following:
+// https://github.com/erincatto/box2d/blob/main/src/collision/b2_collide_circle.cpp
+
 use crate::{
     collision::{
-        algorithms::{
-            gjk::{gjk_collision, gjk_distance},
-            sat::sat,
-        },
+        algorithms::decomposition::signed_area,
         collider::Collider::{CircleCollider, PolygonCollider},
-        manifold::ContactManifold,
+        error::CollisionError,
+        manifold::{ContactBody, ContactFeature, ContactManifold},
     },
-    rigidbody2d::RigidBody2D,
+    linalg::Vec2D,
 };
 
-pub fn sphere_v_polygon(manifold: &mut ContactManifold) {
-    // Ensure a is the circle and b the polygon
-    let (a_radius, b_vertices) = match (&manifold.a.collider, &manifold.b.collider) {
-        (CircleCollider { radius }, PolygonCollider { vertices }) => (*radius, vertices),
-        (PolygonCollider { vertices }, CircleCollider { radius }) => {
-            // a_radius = *radius;
-            // b_vertices = vertices;
-            let tmp = manifold.a;
-            manifold.a = manifold.b;
-            manifold.b = tmp;
-            (*radius, vertices)
-        }
-        _ => panic!("Inappropriate fonction used for the narrow phase."),
+/// The contact of a circle with a polygon, both rounded.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircleContact {
+    /// On the surface of the polygon
+    pub point: Vec2D,
+    /// Going out of the polygon, toward the circle
+    pub normal: Vec2D,
+    pub depth: f64,
+    /// The vertex or the edge of the polygon closest to the center of the circle.
+    pub feature: ContactFeature,
+}
+
+/// Contact of the circle at `center` with the convex polygon given by its world vertices, in
+/// either order: the features are numbered as they are given. The edge of the polygon the center
+/// is the farthest in front of is the closest feature when the center is inside, or in front of
+/// that edge; otherwise the center is past one of its ends, and the closest feature is that
+/// vertex.
+pub fn collide_circle_polygon(
+    center: Vec2D,
+    radius: f64,
+    vertices: &[Vec2D],
+    polygon_radius: f64,
+) -> Option<CircleContact> {
+    let n = vertices.len();
+    let total_radius = radius + polygon_radius;
+
+    // Polygons built by hand can be clockwise.
+    let counter_clockwise = signed_area(vertices) >= 0.;
+    let outward = |v1: Vec2D, v2: Vec2D| {
+        let edge = v2 - v1;
+        let normal = if counter_clockwise {
+            edge.right()
+        } else {
+            edge.left()
+        };
+        normal.normalize()
     };
 
-    // Following: http://media.steampowered.com/apps/valve/2015/DirkGregorius_Contacts.pdf page 54
-
-    let fake_a = RigidBody2D::new(manifold.a.position, CircleCollider { radius: 1. }, 1.);
-
-    // Test if this is shallow collision of not:
-    match gjk_collision(&fake_a, manifold.b) {
-        // Shallow
-        None => match gjk_distance(&fake_a, manifold.b) {
-            // No collision (but should not happen)
-            None => manifold.unset_colliding(),
-            Some((distance, point_a, point_b)) => {
-                // Collision !
-                if distance <= a_radius {
-                    let l = point_b - point_a;
-                    manifold.set_colliding(l.normalize());
-                    manifold.add_point(point_b, (l.norm() - a_radius).abs())
-                } else {
-                    manifold.unset_colliding()
-                }
-            }
-        },
-        Some(_simplex) => {
-            println!("hey !");
-
-            // let CollisionResult { normal, .. } = epa(simplex, manifold.a, manifold.b);
-            let normal = match sat(manifold.a, manifold.b) {
-                Some(n) => n.normalize(),
-                None => panic!("Sat does not give a collision whereas GJK gives one."),
-            };
-
-            println!("sat computed the normal: {normal}");
-
-            let n = b_vertices.len();
-            let mut min_vertex = manifold.b.to_global(b_vertices[0]);
-            let mut min_edge =
-                manifold.b.to_global(b_vertices[1]) - manifold.b.to_global(b_vertices[0]);
-            let mut min_dist = min_edge.normalize().cross(manifold.a.position - min_vertex);
-
-            println!(
-                "Edge 0: {min_edge}: ({} - {}) is at {min_dist}",
-                manifold.b.to_global(b_vertices[1]),
-                min_vertex
-            );
+    let mut edge = 0;
+    let mut separation = f64::NEG_INFINITY;
+
+    for i in 0..n {
+        let normal = outward(vertices[i], vertices[(i + 1) % n]);
+        let s = normal * (center - vertices[i]);
+
+        if s > total_radius {
+            return None;
+        }
+        if s > separation {
+            separation = s;
+            edge = i;
+        }
+    }
+
+    let (v1, v2) = (vertices[edge], vertices[(edge + 1) % n]);
+    let face_normal = outward(v1, v2);
+
+    let face = CircleContact {
+        point: center - face_normal * (separation - polygon_radius),
+        normal: face_normal,
+        depth: total_radius - separation,
+        feature: ContactFeature::Edge(edge),
+    };
 
-            for i in 1..n {
-                let j = (i + 1) % n;
-                let vertex = manifold.b.to_global(b_vertices[i]);
-                let edge = manifold.b.to_global(b_vertices[j]) - vertex;
-                let dist = edge.normalize().cross(manifold.a.position - vertex);
+    // The center is inside the polygon.
+    if separation < f64::EPSILON {
+        return Some(face);
+    }
 
-                println!(
-                    "Edge {i}: {edge}: ({} - {}) is at {dist}",
-                    manifold.b.to_global(b_vertices[j]),
-                    manifold.b.to_global(b_vertices[i])
-                );
-                if dist < min_dist {
-                    min_dist = dist;
-                    min_vertex = vertex;
-                    min_edge = edge;
-                    println!(" Update !, min_dist: {min_dist}");
-                }
-            }
+    let vertex_contact = |vertex: Vec2D, index: usize| {
+        let l = center - vertex;
+        let distance = l.norm();
 
-            println!("The min_edge is: {min_edge}, with vertex: {min_vertex}");
+        if distance > total_radius {
+            return None;
+        }
 
-            let t = ((manifold.a.position - min_vertex) * min_edge) / min_edge.squared_norm();
+        let normal = l / distance;
+        Some(CircleContact {
+            point: vertex + normal * polygon_radius,
+            normal,
+            depth: total_radius - distance,
+            feature: ContactFeature::Vertex(index),
+        })
+    };
 
-            let point = min_vertex + min_edge * t;
+    if (center - v1) * (v2 - v1) <= 0. {
+        vertex_contact(v1, edge)
+    } else if (center - v2) * (v1 - v2) <= 0. {
+        vertex_contact(v2, (edge + 1) % n)
+    } else {
+        Some(face)
+    }
+}
 
-            println!("min_dist: {min_dist}");
+pub fn sphere_v_polygon(manifold: &mut ContactManifold) -> Result<(), CollisionError> {
+    let (circle, polygon, flipped) = match (&manifold.a.collider, &manifold.b.collider) {
+        (CircleCollider { .. }, PolygonCollider { .. }) => (manifold.a, manifold.b, false),
+        (PolygonCollider { .. }, CircleCollider { .. }) => (manifold.b, manifold.a, true),
+        _ => return Err(CollisionError::UnsupportedPair),
+    };
+    let (radius, vertices, polygon_radius) = match (&circle.collider, &polygon.collider) {
+        (
+            CircleCollider { radius },
+            PolygonCollider {
+                vertices,
+                radius: polygon_radius,
+            },
+        ) => (*radius, vertices, *polygon_radius),
+        _ => unreachable!(),
+    };
 
-            let depth = min_dist + a_radius;
+    let vertices: Vec<Vec2D> = vertices.iter().map(|v| polygon.to_global(*v)).collect();
 
-            manifold.set_colliding(normal);
-            manifold.add_point(point, depth);
+    match collide_circle_polygon(circle.position, radius, &vertices, polygon_radius) {
+        None => manifold.unset_colliding(),
+        // The point is on the polygon, and the normal goes toward the circle.
+        Some(contact) if flipped => {
+            manifold.set_colliding(contact.normal);
+            manifold.add_feature_point(
+                contact.point,
+                contact.depth,
+                ContactBody::A,
+                contact.feature,
+            );
+        }
+        Some(contact) => {
+            manifold.set_colliding(-contact.normal);
+            manifold.add_feature_point(
+                contact.point,
+                contact.depth,
+                ContactBody::B,
+                contact.feature,
+            );
         }
     }
+
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        collision::{collider::Collider, manifold::ContactManifold},
+        collision::{
+            collider::Collider,
+            manifold::{ContactBody, ContactFeature, ContactManifold, ContactPoint},
+        },
         linalg::Vec2D,
         rigidbody2d::RigidBody2D,
     };
@@ -122,11 +172,19 @@ mod tests {
         let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
 
         let mut manifold = ContactManifold::new(&a, &b);
-        manifold.compute();
+        manifold.compute().unwrap();
 
         assert_eq!(manifold.normal, Some(Vec2D::new(0., -1.)));
 
-        assert_eq!(manifold.points, vec![(Vec2D::new(0., 1.), 0.5 + 1.)]);
+        assert_eq!(
+            manifold.points,
+            vec![ContactPoint {
+                point: Vec2D::new(0., 1.),
+                depth: 0.5 + 1.,
+                body: ContactBody::B,
+                feature: Some(ContactFeature::Edge(2))
+            }]
+        );
     }
 
     #[test]
@@ -139,11 +197,19 @@ mod tests {
         let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
 
         let mut manifold = ContactManifold::new(&a, &b);
-        manifold.compute();
+        manifold.compute().unwrap();
 
         assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
 
-        assert_eq!(manifold.points, vec![(Vec2D::new(0., -1.), 0.5 + 1.)]);
+        assert_eq!(
+            manifold.points,
+            vec![ContactPoint {
+                point: Vec2D::new(0., -1.),
+                depth: 0.5 + 1.,
+                body: ContactBody::B,
+                feature: Some(ContactFeature::Edge(0))
+            }]
+        );
     }
 
     #[test]
@@ -152,10 +218,112 @@ mod tests {
         let b = RigidBody2D::new(Vec2D::new(4., 0.), Collider::rectangle(10., 4.), 1.);
 
         let mut manifold = ContactManifold::new(&a, &b);
-        manifold.compute();
+        manifold.compute().unwrap();
 
         assert_eq!(manifold.normal, Some(Vec2D::new(1., 0.)));
 
-        assert_eq!(manifold.points, vec![(Vec2D::new(-1., 0.), 1. + 4.)]);
+        assert_eq!(
+            manifold.points,
+            vec![ContactPoint {
+                point: Vec2D::new(-1., 0.),
+                depth: 1. + 4.,
+                body: ContactBody::B,
+                feature: Some(ContactFeature::Edge(3))
+            }]
+        );
+    }
+
+    #[test]
+    fn example_four() {
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(2., 2.), 1.);
+
+        // In front of the top edge
+        let b = RigidBody2D::new(Vec2D::new(0.3, 1.4), Collider::circle(0.5), 1.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        let contact = manifold.points[0];
+        assert!((contact.point - Vec2D::new(0.3, 1.)).near_zero());
+        assert!((contact.depth - 0.1).abs() < 1e-9);
+        assert_eq!(contact.body, ContactBody::A);
+        assert_eq!(contact.feature, Some(ContactFeature::Edge(2)));
+
+        // Past the top right corner
+        let b = RigidBody2D::new(Vec2D::new(1.3, 1.4), Collider::circle(0.6), 1.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0.6, 0.8)).near_zero());
+        let contact = manifold.points[0];
+        assert!((contact.point - Vec2D::new(1., 1.)).near_zero());
+        assert!((contact.depth - 0.1).abs() < 1e-9);
+        assert_eq!(contact.feature, Some(ContactFeature::Vertex(2)));
+
+        let b = RigidBody2D::new(Vec2D::new(1.3, 1.4), Collider::circle(0.4), 1.);
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, None);
+
+        // A rounded box, from the circle: the corner is rounded too.
+        let a = RigidBody2D::new(Vec2D::zero(), Collider::rounded_rectangle(2., 2., 0.2), 1.);
+        let b = RigidBody2D::new(Vec2D::new(1.3, 1.4), Collider::circle(0.4), 1.);
+        let mut manifold = ContactManifold::new(&b, &a);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(-0.6, -0.8)).near_zero());
+        let contact = manifold.points[0];
+        assert!((contact.point - Vec2D::new(1.12, 1.16)).near_zero());
+        assert!((contact.depth - 0.1).abs() < 1e-9);
+        assert_eq!(contact.body, ContactBody::B);
+        assert_eq!(contact.feature, Some(ContactFeature::Vertex(2)));
+    }
+
+    #[test]
+    fn example_five() {
+        // A clockwise square, built by hand
+        let square = RigidBody2D::new(
+            Vec2D::zero(),
+            Collider::PolygonCollider {
+                vertices: vec![
+                    Vec2D::new(-1., -1.),
+                    Vec2D::new(-1., 1.),
+                    Vec2D::new(1., 1.),
+                    Vec2D::new(1., -1.),
+                ],
+                radius: 0.,
+            },
+            1.,
+        );
+
+        // Overlapping its top by 0.1
+        let b = RigidBody2D::new(Vec2D::new(0., 1.4), Collider::circle(0.5), 1.);
+        let mut manifold = ContactManifold::new(&square, &b);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., 1.)));
+        let contact = manifold.points[0];
+        assert!((contact.point - Vec2D::new(0., 1.)).near_zero());
+        assert!((contact.depth - 0.1).abs() < 1e-9);
+        assert_eq!(contact.feature, Some(ContactFeature::Edge(1)));
+
+        // Inside it, closest to its top
+        let b = RigidBody2D::new(Vec2D::new(0., 0.5), Collider::circle(0.2), 1.);
+        let mut manifold = ContactManifold::new(&b, &square);
+        manifold.compute().unwrap();
+
+        assert_eq!(manifold.normal, Some(Vec2D::new(0., -1.)));
+        assert!((manifold.points[0].depth - 0.7).abs() < 1e-9);
+
+        // Past its corner
+        let b = RigidBody2D::new(Vec2D::new(1.3, 1.4), Collider::circle(0.6), 1.);
+        let mut manifold = ContactManifold::new(&square, &b);
+        manifold.compute().unwrap();
+
+        assert!((manifold.normal.unwrap() - Vec2D::new(0.6, 0.8)).near_zero());
+        assert_eq!(manifold.points[0].feature, Some(ContactFeature::Vertex(2)));
     }
 }
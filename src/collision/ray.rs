@@ -0,0 +1,449 @@
+use crate::{
+    collision::algorithms::{
+        heightfield::{cell_at, sample},
+        segment::closest_point_on_segment,
+    },
+    linalg::Vec2D,
+};
+
+/// The segment `origin + t * direction` for `t` in `[0, max_fraction]`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ray {
+    pub origin: Vec2D,
+    pub direction: Vec2D,
+    pub max_fraction: f64,
+}
+
+impl Ray {
+    pub fn new(origin: Vec2D, direction: Vec2D, max_fraction: f64) -> Self {
+        Self {
+            origin,
+            direction,
+            max_fraction,
+        }
+    }
+
+    /// The ray going from `start` to `end`, fractions are then in `[0, 1]`.
+    pub fn between(start: Vec2D, end: Vec2D) -> Self {
+        Self::new(start, end - start, 1.)
+    }
+
+    pub fn point_at(&self, fraction: f64) -> Vec2D {
+        self.origin + self.direction * fraction
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RayHit {
+    /// Where the ray enters the shape, as `ray.point_at(fraction)`.
+    pub fraction: f64,
+    pub point: Vec2D,
+    /// Unit normal of the surface at `point`, pointing out of the shape.
+    pub normal: Vec2D,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RaycastMode {
+    /// Only the hit the closest to the origin of the ray.
+    Closest,
+    /// The first hit found, whichever it is. The cheapest one, for line of sight tests.
+    Any,
+    /// Every hit, sorted from the closest to the farthest.
+    All,
+}
+
+/// From: https://github.com/erincatto/box2d/blob/main/src/collision/b2_circle_shape.cpp
+pub fn raycast_circle(ray: &Ray, center: Vec2D, radius: f64) -> Option<RayHit> {
+    let s = ray.origin - center;
+    let b = s.squared_norm() - radius * radius;
+
+    // Rays starting inside the circle don't hit it.
+    if b < 0. {
+        return None;
+    }
+
+    let c = s * ray.direction;
+    let rr = ray.direction.squared_norm();
+    let sigma = c * c - rr * b;
+
+    if sigma < 0. || rr == 0. {
+        return None;
+    }
+
+    let fraction = -(c + sigma.sqrt()) / rr;
+
+    if fraction < 0. || fraction > ray.max_fraction {
+        return None;
+    }
+
+    let point = ray.point_at(fraction);
+
+    Some(RayHit {
+        fraction,
+        point,
+        normal: (point - center).normalize(),
+    })
+}
+
+/// Clips the ray against the half-planes of each edge of a convex polygon.
+///
+/// From: https://github.com/erincatto/box2d/blob/main/src/collision/b2_polygon_shape.cpp
+pub fn raycast_polygon(ray: &Ray, vertices: &[Vec2D]) -> Option<RayHit> {
+    let n = vertices.len();
+    let center = vertices.iter().fold(Vec2D::zero(), |acc, v| acc + *v) / n as f64;
+
+    let mut lower = 0.;
+    let mut upper = ray.max_fraction;
+    let mut hit_normal = None;
+
+    for i in 0..n {
+        let j = (i + 1) % n;
+        let mut normal = (vertices[j] - vertices[i]).right().normalize();
+
+        // Don't rely on the winding of the polygon.
+        if normal * (vertices[i] - center) < 0. {
+            normal = -normal;
+        }
+
+        let numerator = normal * (vertices[i] - ray.origin);
+        let denominator = normal * ray.direction;
+
+        if denominator == 0. {
+            // Parallel to the edge and outside of it
+            if numerator < 0. {
+                return None;
+            }
+        } else if denominator < 0. && numerator < lower * denominator {
+            // The ray enters this half-plane
+            lower = numerator / denominator;
+            hit_normal = Some(normal);
+        } else if denominator > 0. && numerator < upper * denominator {
+            // The ray exits this half-plane
+            upper = numerator / denominator;
+        }
+
+        if upper < lower {
+            return None;
+        }
+    }
+
+    // When no edge was entered, the origin of the ray is inside the polygon.
+    hit_normal.map(|normal| RayHit {
+        fraction: lower,
+        point: ray.point_at(lower),
+        normal,
+    })
+}
+
+/// Segments are two-sided: the normal faces the origin of the ray.
+///
+/// From: https://github.com/erincatto/box2d/blob/main/src/collision/b2_edge_shape.cpp
+pub fn raycast_segment(ray: &Ray, start: Vec2D, end: Vec2D) -> Option<RayHit> {
+    let edge = end - start;
+
+    if edge.near_zero() {
+        return None;
+    }
+
+    let mut normal = edge.right().normalize();
+    let numerator = normal * (start - ray.origin);
+    let denominator = normal * ray.direction;
+
+    // Parallel to the segment
+    if denominator == 0. {
+        return None;
+    }
+
+    let fraction = numerator / denominator;
+
+    if fraction < 0. || fraction > ray.max_fraction {
+        return None;
+    }
+
+    let point = ray.point_at(fraction);
+    let s = (point - start) * edge / edge.squared_norm();
+
+    if !(0. ..=1.).contains(&s) {
+        return None;
+    }
+
+    if numerator > 0. {
+        normal = -normal;
+    }
+
+    Some(RayHit {
+        fraction,
+        point,
+        normal,
+    })
+}
+
+/// Marches through the cells of the heightfield crossed by the ray, from its origin: the first
+/// segment hit is the closest one.
+pub fn raycast_heightfield(ray: &Ray, heights: &[f64], spacing: f64) -> Option<RayHit> {
+    let width = spacing * (heights.len() - 1) as f64;
+    let mut lower = 0.;
+    let mut upper = ray.max_fraction;
+
+    // The part of the ray above the heightfield
+    if ray.direction.x == 0. {
+        if ray.origin.x < 0. || ray.origin.x > width {
+            return None;
+        }
+    } else {
+        let t1 = -ray.origin.x / ray.direction.x;
+        let t2 = (width - ray.origin.x) / ray.direction.x;
+
+        lower = t1.min(t2).max(lower);
+        upper = t1.max(t2).min(upper);
+
+        if upper < lower {
+            return None;
+        }
+    }
+
+    let first = cell_at(heights, spacing, ray.point_at(lower).x);
+    let last = cell_at(heights, spacing, ray.point_at(upper).x);
+    let mut cell = first;
+
+    loop {
+        let hit = raycast_segment(
+            ray,
+            sample(heights, spacing, cell),
+            sample(heights, spacing, cell + 1),
+        );
+
+        if hit.is_some() || cell == last {
+            return hit;
+        }
+
+        if first < last {
+            cell += 1;
+        } else {
+            cell -= 1;
+        }
+    }
+}
+
+/// The capsule is the union of a rectangle and of two discs: the first one entered is the hit.
+pub fn raycast_capsule(ray: &Ray, p1: Vec2D, p2: Vec2D, radius: f64) -> Option<RayHit> {
+    let (closest, _) = closest_point_on_segment(ray.origin, p1, p2);
+
+    // Rays starting inside the capsule don't hit it.
+    if (ray.origin - closest).squared_norm() < radius * radius {
+        return None;
+    }
+
+    let mut hits = vec![
+        raycast_circle(ray, p1, radius),
+        raycast_circle(ray, p2, radius),
+    ];
+
+    let axis = p2 - p1;
+    if !axis.near_zero() {
+        let offset = axis.right().normalize() * radius;
+        hits.push(raycast_polygon(
+            ray,
+            &[p1 + offset, p2 + offset, p2 - offset, p1 - offset],
+        ));
+    }
+
+    hits.into_iter()
+        .flatten()
+        .min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::Collider,
+            ray::{Ray, RayHit},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        let circle = Collider::circle(1.);
+
+        assert_eq!(
+            circle
+                .raycast(&Ray::new(Vec2D::new(-3., 0.), Vec2D::new(1., 0.), 10.))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 2.,
+                point: Vec2D::new(-1., 0.),
+                normal: Vec2D::new(-1., 0.)
+            })
+        );
+
+        // Too short
+        assert_eq!(
+            circle
+                .raycast(&Ray::new(Vec2D::new(-3., 0.), Vec2D::new(1., 0.), 1.5))
+                .unwrap(),
+            None
+        );
+        // Passing by
+        assert_eq!(
+            circle
+                .raycast(&Ray::between(Vec2D::new(-3., 2.), Vec2D::new(3., 2.)))
+                .unwrap(),
+            None
+        );
+        // Starting inside
+        assert_eq!(
+            circle
+                .raycast(&Ray::new(Vec2D::zero(), Vec2D::new(1., 0.), 10.))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn example_two() {
+        let rectangle = Collider::rectangle(2., 2.);
+
+        assert_eq!(
+            rectangle
+                .raycast(&Ray::between(Vec2D::new(0.5, 4.), Vec2D::new(0.5, -4.)))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 0.375,
+                point: Vec2D::new(0.5, 1.),
+                normal: Vec2D::new(0., 1.)
+            })
+        );
+
+        assert_eq!(
+            rectangle
+                .raycast(&Ray::between(Vec2D::new(2., 4.), Vec2D::new(2., -4.)))
+                .unwrap(),
+            None
+        );
+
+        // Same polygon with the other winding
+        let reversed = Collider::PolygonCollider {
+            vertices: vec![
+                Vec2D::new(-1., 1.),
+                Vec2D::new(1., 1.),
+                Vec2D::new(1., -1.),
+                Vec2D::new(-1., -1.),
+            ],
+            radius: 0.,
+        };
+
+        assert_eq!(
+            reversed
+                .raycast(&Ray::between(Vec2D::new(-3., 0.), Vec2D::new(3., 0.)))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 1. / 3.,
+                point: Vec2D::new(-1., 0.),
+                normal: Vec2D::new(-1., 0.)
+            })
+        );
+    }
+
+    #[test]
+    fn example_three() {
+        let mut body = RigidBody2D::new(Vec2D::new(10., 0.), Collider::rectangle(2., 2.), 1.);
+        body.angle = std::f64::consts::FRAC_PI_4;
+
+        let hit = body
+            .raycast(&Ray::new(Vec2D::new(0., 0.2), Vec2D::new(1., 0.), 100.))
+            .unwrap()
+            .unwrap();
+
+        assert!((hit.fraction - (10.2 - 2_f64.sqrt())).abs() < 1e-9);
+        assert!((hit.point - Vec2D::new(10.2 - 2_f64.sqrt(), 0.2)).near_zero());
+        assert!((hit.normal - Vec2D::new(-1., 1.).normalize()).near_zero());
+    }
+
+    #[test]
+    fn example_four() {
+        let capsule = Collider::capsule(2., 1.);
+
+        // On the flat side
+        assert_eq!(
+            capsule
+                .raycast(&Ray::between(Vec2D::new(1., 5.), Vec2D::new(1., -5.)))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 0.4,
+                point: Vec2D::new(1., 1.),
+                normal: Vec2D::new(0., 1.)
+            })
+        );
+
+        // On the rounded end
+        assert_eq!(
+            capsule
+                .raycast(&Ray::between(Vec2D::new(6., 0.), Vec2D::new(0., 0.)))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 0.5,
+                point: Vec2D::new(3., 0.),
+                normal: Vec2D::new(1., 0.)
+            })
+        );
+
+        assert_eq!(
+            capsule
+                .raycast(&Ray::between(Vec2D::new(3.1, 5.), Vec2D::new(3.1, -5.)))
+                .unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn example_five() {
+        let segment = Collider::segment(Vec2D::new(-1., 0.), Vec2D::new(1., 0.));
+
+        // From both sides
+        assert_eq!(
+            segment
+                .raycast(&Ray::between(Vec2D::new(0.5, 2.), Vec2D::new(0.5, -2.)))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 0.5,
+                point: Vec2D::new(0.5, 0.),
+                normal: Vec2D::new(0., 1.)
+            })
+        );
+        assert_eq!(
+            segment
+                .raycast(&Ray::between(Vec2D::new(0.5, -2.), Vec2D::new(0.5, 2.)))
+                .unwrap(),
+            Some(RayHit {
+                fraction: 0.5,
+                point: Vec2D::new(0.5, 0.),
+                normal: Vec2D::new(0., -1.)
+            })
+        );
+        assert_eq!(
+            segment
+                .raycast(&Ray::between(Vec2D::new(1.5, 2.), Vec2D::new(1.5, -2.)))
+                .unwrap(),
+            None
+        );
+
+        // The closest of the segments of the chain
+        let chain = Collider::chain(&[
+            Vec2D::new(0., 0.),
+            Vec2D::new(2., 0.),
+            Vec2D::new(2., 2.),
+            Vec2D::new(4., 2.),
+        ])
+        .unwrap();
+
+        let hit = chain
+            .raycast(&Ray::between(Vec2D::new(5., 1.), Vec2D::new(-1., 1.)))
+            .unwrap()
+            .unwrap();
+        assert_eq!(hit.point, Vec2D::new(2., 1.));
+        assert_eq!(hit.normal, Vec2D::new(1., 0.));
+    }
+}
@@ -0,0 +1,178 @@
+use std::{f64::consts::PI, fmt::Debug};
+
+use crate::{
+    collision::{aabb::Aabb, algorithms::hull::convex_hull, collider::Collider},
+    linalg::Vec2D,
+    rigidbody2d::RigidBody2D,
+};
+
+/// How many directions are sampled to approximate a shape known by its support function only.
+const SAMPLES: usize = 32;
+
+/// A convex shape known by its support function, which is all GJK and EPA need.
+///
+/// Implement it to collide shapes the `Collider` enum doesn't have, through
+/// `Collider::ConvexCollider`. Everything but `support` has a default, approximated from a
+/// polygon inscribed in the shape: override them when the exact value is known.
+pub trait SupportMap: Debug {
+    /// The farthest point of the shape in `direction`, which may not be normalised.
+    fn support(&self, direction: Vec2D) -> Vec2D;
+
+    /// A point inside the shape, where GJK starts from.
+    fn center(&self) -> Vec2D {
+        self.local_aabb().center()
+    }
+
+    fn local_aabb(&self) -> Aabb {
+        Aabb::from_points(&[
+            self.support(Vec2D::new(1., 0.)),
+            self.support(Vec2D::new(-1., 0.)),
+            self.support(Vec2D::new(0., 1.)),
+            self.support(Vec2D::new(0., -1.)),
+        ])
+    }
+
+    fn area(&self) -> f64 {
+        Collider::PolygonCollider {
+            vertices: support_polygon(self),
+            radius: 0.,
+        }
+        .area()
+    }
+
+    /// Moment of inertia around the local origin, for a uniform density.
+    fn inertia(&self, mass: f64) -> f64 {
+        Collider::PolygonCollider {
+            vertices: support_polygon(self),
+            radius: 0.,
+        }
+        .get_inertia(mass)
+    }
+}
+
+/// The polygon inscribed in the shape through its support points in evenly spread directions,
+/// counter-clockwise.
+pub fn support_polygon<S: SupportMap + ?Sized>(shape: &S) -> Vec<Vec2D> {
+    let points: Vec<Vec2D> = (0..SAMPLES)
+        .map(|i| {
+            let angle = 2. * PI * i as f64 / SAMPLES as f64;
+            shape.support(Vec2D::new(angle.cos(), angle.sin()))
+        })
+        .collect();
+
+    convex_hull(&points)
+}
+
+impl SupportMap for Collider {
+    fn support(&self, direction: Vec2D) -> Vec2D {
+        Collider::support(self, direction)
+    }
+
+    fn center(&self) -> Vec2D {
+        Collider::center(self)
+    }
+
+    fn local_aabb(&self) -> Aabb {
+        Collider::local_aabb(self)
+    }
+
+    fn area(&self) -> f64 {
+        Collider::area(self)
+    }
+
+    fn inertia(&self, mass: f64) -> f64 {
+        self.get_inertia(mass)
+    }
+}
+
+/// A body is its collider placed in world space.
+impl SupportMap for RigidBody2D {
+    fn support(&self, direction: Vec2D) -> Vec2D {
+        let local_direction = self.to_local(direction);
+        self.to_global(self.collider.support(local_direction))
+    }
+
+    fn center(&self) -> Vec2D {
+        self.position
+    }
+
+    fn local_aabb(&self) -> Aabb {
+        self.world_aabb()
+    }
+
+    fn area(&self) -> f64 {
+        self.collider.area()
+    }
+
+    fn inertia(&self, mass: f64) -> f64 {
+        self.collider.get_inertia(mass)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            aabb::Aabb, collider::Collider, manifold::ContactManifold, ray::Ray,
+            support_map::SupportMap,
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    /// A square standing on one of its corners, as a downstream crate would define it
+    #[derive(Debug)]
+    struct Diamond {
+        half_diagonal: f64,
+    }
+
+    impl SupportMap for Diamond {
+        fn support(&self, direction: Vec2D) -> Vec2D {
+            let d = self.half_diagonal;
+
+            [
+                Vec2D::new(d, 0.),
+                Vec2D::new(0., d),
+                Vec2D::new(-d, 0.),
+                Vec2D::new(0., -d),
+            ]
+            .into_iter()
+            .max_by(|a, b| (*a * direction).total_cmp(&(*b * direction)))
+            .unwrap()
+        }
+    }
+
+    #[test]
+    fn example_one() {
+        let diamond = Collider::convex(Diamond { half_diagonal: 1. });
+
+        // The sampled polygon is the diamond itself.
+        assert!((diamond.area() - 2.).abs() < 1e-9);
+        assert!((diamond.get_inertia(3.) - 1.).abs() < 1e-9);
+        assert_eq!(
+            diamond.local_aabb(),
+            Aabb::new(Vec2D::new(-1., -1.), Vec2D::new(1., 1.))
+        );
+
+        assert!(diamond.contains_point(Vec2D::new(0.4, 0.4)));
+        assert!(!diamond.contains_point(Vec2D::new(0.6, 0.6)));
+
+        let hit = diamond
+            .raycast(&Ray::new(Vec2D::new(-3., 0.5), Vec2D::new(1., 0.), 10.))
+            .unwrap()
+            .unwrap();
+        assert!((hit.point - Vec2D::new(-0.5, 0.5)).near_zero());
+        assert!((hit.normal - Vec2D::new(-1., 1.).normalize()).near_zero());
+
+        // Falling on its tip onto a box, through GJK and EPA
+        let a = RigidBody2D::new(Vec2D::new(0., 1.9), diamond, 1.);
+        let b = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(4., 2.), 0.);
+
+        let mut manifold = ContactManifold::new(&a, &b);
+        manifold.compute().unwrap();
+
+        let normal = manifold.normal.unwrap();
+        assert!((normal - Vec2D::new(0., -1.)).near_zero());
+        assert!((manifold.points[0].depth - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,231 @@
+// Conservative advancement.
+// From: Brian Mirtich, Impulse-based Dynamic Simulation of Rigid Body Systems, chapter 2
+// And: https://box2d.org/files/ErinCatto_ContinuousCollision_GDC2013.pdf
+
+use crate::{
+    collision::{distance::distance, error::CollisionError},
+    linalg::{Vec2D, TOLERANCE},
+    rigidbody2d::RigidBody2D,
+};
+
+const TOI_ITERATIONS: u32 = 100;
+
+/// The motion of a body over an interval, going linearly from its pose at the start to the one
+/// at the end. The body turns around its position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sweep {
+    pub start_position: Vec2D,
+    pub start_angle: f64,
+    pub end_position: Vec2D,
+    pub end_angle: f64,
+}
+
+impl Sweep {
+    pub fn new(
+        start_position: Vec2D,
+        start_angle: f64,
+        end_position: Vec2D,
+        end_angle: f64,
+    ) -> Self {
+        Self {
+            start_position,
+            start_angle,
+            end_position,
+            end_angle,
+        }
+    }
+
+    /// A body not moving from its current pose.
+    pub fn still(body: &RigidBody2D) -> Self {
+        Self::new(body.position, body.angle, body.position, body.angle)
+    }
+
+    /// A body moving at its current velocities for `dt` seconds.
+    pub fn from_velocity(body: &RigidBody2D, dt: f64) -> Self {
+        Self::new(
+            body.position,
+            body.angle,
+            body.position + body.velocity * dt,
+            body.angle + body.angular_velocity * dt,
+        )
+    }
+
+    /// `body` at the fraction `t` of the interval.
+    fn place(&self, body: &RigidBody2D, t: f64) -> RigidBody2D {
+        let mut placed = body.clone();
+        placed.position = self.start_position + (self.end_position - self.start_position) * t;
+        placed.angle = self.start_angle + (self.end_angle - self.start_angle) * t;
+        placed
+    }
+}
+
+/// How two moving bodies meet over an interval.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeOfImpact {
+    /// They never touch.
+    Separated,
+    /// They already overlap at the start.
+    Overlapping,
+    /// They first touch at the fraction `time` of the interval, at `point`, with `normal` going
+    /// from `a` to `b`.
+    Impact {
+        time: f64,
+        point: Vec2D,
+        normal: Vec2D,
+    },
+}
+
+/// When `a` and `b`, moving along `sweep_a` and `sweep_b`, first touch, both the translation and
+/// the rotation being accounted for. Their current poses are ignored.
+///
+/// The bodies are moved forward by as much as they can without their closest points meeting,
+/// bounding how fast a point turning around the position of its body can go. The impact is found
+/// when they are less than `TOLERANCE` apart.
+pub fn time_of_impact(
+    a: &RigidBody2D,
+    sweep_a: &Sweep,
+    b: &RigidBody2D,
+    sweep_b: &Sweep,
+) -> Result<TimeOfImpact, CollisionError> {
+    let target = TOLERANCE;
+    let tolerance = 0.25 * TOLERANCE;
+
+    let translation = (sweep_b.end_position - sweep_b.start_position)
+        - (sweep_a.end_position - sweep_a.start_position);
+    let rotation_bound = (sweep_a.end_angle - sweep_a.start_angle).abs() * a.bounding_radius()
+        + (sweep_b.end_angle - sweep_b.start_angle).abs() * b.bounding_radius();
+
+    let mut t = 0.;
+
+    for _ in 0..TOI_ITERATIONS {
+        let result = distance(&sweep_a.place(a, t), &sweep_b.place(b, t))?;
+
+        if t == 0. && result.separation < 0. {
+            return Ok(TimeOfImpact::Overlapping);
+        }
+
+        if result.separation < target + tolerance {
+            return Ok(TimeOfImpact::Impact {
+                time: t,
+                point: (result.point_a + result.point_b) / 2.,
+                normal: result.normal,
+            });
+        }
+
+        // How fast the closest points can get closer, over the whole interval
+        let approach = -(translation * result.normal) + rotation_bound;
+
+        if approach <= 0. {
+            return Ok(TimeOfImpact::Separated);
+        }
+
+        t += (result.separation - target) / approach;
+
+        if t >= 1. {
+            return Ok(TimeOfImpact::Separated);
+        }
+    }
+
+    Err(CollisionError::NoConvergence)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            collider::Collider,
+            time_of_impact::{time_of_impact, Sweep, TimeOfImpact},
+        },
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    #[test]
+    fn example_one() {
+        // A ball flying toward a paddle
+        let ball = RigidBody2D::new(Vec2D::zero(), Collider::circle(0.5), 1.);
+        let paddle = RigidBody2D::new(Vec2D::new(5., 0.), Collider::rectangle(1., 4.), 1.);
+
+        let sweep = Sweep::new(Vec2D::zero(), 0., Vec2D::new(10., 0.), 0.);
+        let result = time_of_impact(&ball, &sweep, &paddle, &Sweep::still(&paddle)).unwrap();
+
+        match result {
+            TimeOfImpact::Impact {
+                time,
+                point,
+                normal,
+            } => {
+                assert!((time - 0.4).abs() < 1e-3);
+                assert!((point - Vec2D::new(4.5, 0.)).near_zero());
+                assert!((normal - Vec2D::new(1., 0.)).near_zero());
+            }
+            _ => panic!("The ball misses the paddle: {result:?}"),
+        }
+
+        // The paddle coming too: they meet half way.
+        let paddle_sweep = Sweep::new(Vec2D::new(5., 0.), 0., Vec2D::new(-5., 0.), 0.);
+        match time_of_impact(&ball, &sweep, &paddle, &paddle_sweep).unwrap() {
+            TimeOfImpact::Impact { time, .. } => assert!((time - 0.2).abs() < 1e-3),
+            result => panic!("The ball misses the paddle: {result:?}"),
+        }
+
+        // Above it
+        let sweep = Sweep::new(Vec2D::new(0., 3.), 0., Vec2D::new(10., 3.), 0.);
+        assert_eq!(
+            time_of_impact(&ball, &sweep, &paddle, &Sweep::still(&paddle)).unwrap(),
+            TimeOfImpact::Separated
+        );
+
+        // Stopping short of it
+        let sweep = Sweep::new(Vec2D::zero(), 0., Vec2D::new(3.5, 0.), 0.);
+        assert_eq!(
+            time_of_impact(&ball, &sweep, &paddle, &Sweep::still(&paddle)).unwrap(),
+            TimeOfImpact::Separated
+        );
+
+        // Already in it
+        let sweep = Sweep::new(Vec2D::new(4.8, 0.), 0., Vec2D::new(10., 0.), 0.);
+        assert_eq!(
+            time_of_impact(&ball, &sweep, &paddle, &Sweep::still(&paddle)).unwrap(),
+            TimeOfImpact::Overlapping
+        );
+    }
+
+    #[test]
+    fn example_two() {
+        // A bat swinging a quarter turn, and a ball on its way
+        let bat = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(4., 0.2), 1.);
+        let ball = RigidBody2D::new(Vec2D::new(1., 1.), Collider::circle(0.25), 1.);
+
+        let swing = Sweep::new(
+            Vec2D::zero(),
+            0.,
+            Vec2D::zero(),
+            std::f64::consts::FRAC_PI_2,
+        );
+        let result = time_of_impact(&bat, &swing, &ball, &Sweep::still(&ball)).unwrap();
+
+        // The face of the bat reaches the ball when the center of the ball is 0.35 away from it.
+        let angle = std::f64::consts::FRAC_PI_4 - (0.35 / std::f64::consts::SQRT_2).asin();
+
+        match result {
+            TimeOfImpact::Impact { time, normal, .. } => {
+                assert!((time - angle / std::f64::consts::FRAC_PI_2).abs() < 1e-3);
+                assert!((normal - Vec2D::new(-angle.sin(), angle.cos())).norm() < 1e-2);
+            }
+            _ => panic!("The bat misses the ball: {result:?}"),
+        }
+
+        // Swinging the other way
+        let swing = Sweep::new(
+            Vec2D::zero(),
+            0.,
+            Vec2D::zero(),
+            -std::f64::consts::FRAC_PI_2,
+        );
+        assert_eq!(
+            time_of_impact(&bat, &swing, &ball, &Sweep::still(&ball)).unwrap(),
+            TimeOfImpact::Separated
+        );
+    }
+}
@@ -1,6 +1,42 @@
-use crate::{linalg::Matrix, rigidbody2d::RigidBody2D};
+use crate::{
+    linalg::{LinalgError, Matrix, Vec2D},
+    rigidbody2d::RigidBody2D,
+};
 use derivative::Derivative;
 
+pub mod distance_joint;
+pub mod prismatic_joint;
+pub mod revolute_joint;
+
+/// How much of the position error is removed at each step by rigid constraints.
+pub const BAUMGARTE: f64 = 0.2;
+
+/// Drives a joint at a target speed, with a bounded effort.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Motor {
+    pub speed: f64,
+    /// The most force it applies, a torque when it turns.
+    pub max_force: f64,
+}
+
+/// Something tying two bodies of the engine together, solved on their velocities.
+pub trait Joint {
+    /// The indices of the two bodies in the engine.
+    fn bodies(&self) -> (usize, usize);
+
+    /// Forgets the lambda accumulated over the previous step.
+    fn reset(&mut self);
+
+    /// One iteration of the solver, changing the velocities of `a` and `b`. Fails when the joint
+    /// has no effective mass, e.g. between two static bodies.
+    fn solve(
+        &mut self,
+        a: &mut RigidBody2D,
+        b: &mut RigidBody2D,
+        dt: f64,
+    ) -> Result<(), LinalgError>;
+}
+
 #[derive(Derivative)]
 #[derivative(Debug)]
 pub struct Constraint<'a> {
@@ -11,11 +47,14 @@ pub struct Constraint<'a> {
     #[derivative(Debug = "ignore")]
     pub b: &'a RigidBody2D,
 
+    /// Bounds of the accumulated lambda: a constraint only pulling has `c_max` at 0.
     pub c_min: f64,
     pub c_max: f64,
 
     pub jacobian: Matrix,
     pub push_factor: Matrix,
+    /// How much the accumulated lambda holds the constraint back, 0 for a rigid one.
+    pub softness: f64,
 
     pub lambda_accumulated: Matrix,
 }
@@ -35,9 +74,46 @@ impl<'a> Constraint<'a> {
             jacobian,
             lambda_accumulated,
             push_factor,
+            softness: 0.,
         }
     }
 
+    /// A constraint on one scalar: `jacobian` is the velocity of `a` then `b` it depends on.
+    pub fn row(
+        a: &'a RigidBody2D,
+        b: &'a RigidBody2D,
+        jacobian: [f64; 6],
+        c_min: f64,
+        c_max: f64,
+        dt: f64,
+    ) -> Self {
+        let mut constraint = Self::new(a, b, c_min, c_max, dt);
+        constraint.jacobian = Matrix::from(vec![jacobian.to_vec()]);
+        constraint.push_factor = Matrix::zeroes(1, 1);
+        constraint.lambda_accumulated = Matrix::zeroes(1, 1);
+
+        constraint
+    }
+
+    /// Turns a one row constraint of value `c` into a damped spring.
+    /// From: https://box2d.org/files/ErinCatto_SoftConstraints_GDC2011.pdf
+    pub fn set_spring(&mut self, c: f64, frequency: f64, damping_ratio: f64) {
+        let k =
+            (&(&self.jacobian * &self.get_inverted_mass()) * &self.jacobian.transpose())[(0, 0)];
+
+        if k == 0. {
+            return;
+        }
+
+        let mass = 1. / k;
+        let omega = 2. * std::f64::consts::PI * frequency;
+        let stiffness = mass * omega * omega;
+        let damping = 2. * mass * damping_ratio * omega;
+
+        self.softness = 1. / (damping + self.dt * stiffness);
+        self.push_factor[(0, 0)] = -c * stiffness * self.softness;
+    }
+
     fn get_v_1(&self) -> Matrix {
         let mut v_1 = Matrix::zeroes(6, 1);
         v_1[(0, 0)] = self.a.velocity.x;
@@ -64,19 +140,114 @@ impl<'a> Constraint<'a> {
         m_inv
     }
 
-    pub fn get_lambda(&self) -> Matrix {
+    /// Fails when the constraint has no effective mass, e.g. between two static bodies.
+    pub fn get_lambda(&self) -> Result<Matrix, LinalgError> {
         let mut lambda = &self.jacobian * &self.get_v_1();
         lambda *= -1.;
 
         lambda += &self.push_factor;
+        lambda -= &self.lambda_accumulated * self.softness;
 
         let mut denom = &(&self.jacobian * &self.get_inverted_mass()) * &self.jacobian.transpose();
         denom *= self.dt;
+        denom += Matrix::id(denom.shape().0) * self.softness;
 
-        denom = denom.inv();
+        denom = denom.inv()?;
 
-        lambda * denom
+        Ok(denom * lambda)
         // (&self.push_factor - (&self.jacobian * &self.get_v_1()))
         //     * (self.jacobian * self.get_inverted_mass() * self.jacobian.transpose() * self.dt).inv()
     }
+
+    /// Adds the lambda to the accumulated one, within `c_min` and `c_max`, and returns the change
+    /// of the velocities of `a` then `b` it makes.
+    pub fn solve(&mut self) -> Result<Matrix, LinalgError> {
+        let lambda = self.get_lambda()?;
+        let previous = self.lambda_accumulated.clone();
+
+        for i in 0..lambda.shape().0 {
+            self.lambda_accumulated[(i, 0)] =
+                (previous[(i, 0)] + lambda[(i, 0)]).clamp(self.c_min, self.c_max);
+        }
+
+        let mut change = &(&self.get_inverted_mass() * &self.jacobian.transpose())
+            * &(&self.lambda_accumulated - &previous);
+        change *= self.dt;
+
+        Ok(change)
+    }
+}
+
+/// The jacobian of the distance between `anchor_a` on `a` and `anchor_b` on `b`, both in world
+/// space, along `normal`.
+pub fn linear_jacobian(
+    a: &RigidBody2D,
+    b: &RigidBody2D,
+    anchor_a: Vec2D,
+    anchor_b: Vec2D,
+    normal: Vec2D,
+) -> [f64; 6] {
+    let r_a = anchor_a - a.position;
+    let r_b = anchor_b - b.position;
+
+    [
+        -normal.x,
+        -normal.y,
+        -r_a.cross(normal),
+        normal.x,
+        normal.y,
+        r_b.cross(normal),
+    ]
+}
+
+/// The jacobian of the angle of `b` relative to `a`.
+pub const ANGULAR_JACOBIAN: [f64; 6] = [0., 0., -1., 0., 0., 1.];
+
+/// Solves one row of a joint between `a` and `b` and applies it to their velocities, the lambda
+/// accumulated over the step being kept in `lambda`. `setup` sets the push factor.
+pub fn solve_row<F>(
+    a: &mut RigidBody2D,
+    b: &mut RigidBody2D,
+    jacobian: [f64; 6],
+    (c_min, c_max): (f64, f64),
+    lambda: &mut f64,
+    dt: f64,
+    setup: F,
+) -> Result<(), LinalgError>
+where
+    F: FnOnce(&mut Constraint),
+{
+    let change = {
+        let mut row = Constraint::row(a, b, jacobian, c_min, c_max, dt);
+        row.lambda_accumulated[(0, 0)] = *lambda;
+        setup(&mut row);
+
+        let change = row.solve()?;
+        *lambda = row.lambda_accumulated[(0, 0)];
+
+        change
+    };
+
+    apply_velocity_change(a, b, &change);
+
+    Ok(())
+}
+
+/// Applies the change of velocities returned by `Constraint::solve`.
+fn apply_velocity_change(a: &mut RigidBody2D, b: &mut RigidBody2D, change: &Matrix) {
+    a.velocity += Vec2D::new(change[(0, 0)], change[(1, 0)]);
+    a.angular_velocity += change[(2, 0)];
+
+    b.velocity += Vec2D::new(change[(3, 0)], change[(4, 0)]);
+    b.angular_velocity += change[(5, 0)];
+}
+
+/// The push factor of a limit of value `c`, only allowed on one side of 0: it can be reached in
+/// one step, and is pushed back from when passed.
+pub fn limit_push(c: f64, allowed_positive: bool, dt: f64) -> f64 {
+    if (c >= 0.) == allowed_positive {
+        -c / dt
+    } else {
+        -BAUMGARTE * c / dt
+    }
 }
@@ -0,0 +1,250 @@
+use crate::{
+    constraint::{limit_push, linear_jacobian, solve_row, Joint, BAUMGARTE},
+    linalg::{LinalgError, Vec2D},
+    rigidbody2d::RigidBody2D,
+};
+
+/// Makes a joint soft instead of rigid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spring {
+    /// In Hz. A spring of frequency 0 doesn't pull at all.
+    pub frequency: f64,
+    /// 1 for critical damping, 0 for none.
+    pub damping_ratio: f64,
+}
+
+/// Keeps an anchor point on `a` at `length` from one on `b`, both anchors being in the frame of
+/// their body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistanceJoint {
+    pub a: usize,
+    pub b: usize,
+    pub local_anchor_a: Vec2D,
+    pub local_anchor_b: Vec2D,
+    pub length: f64,
+    /// The length is held rigidly without one.
+    pub spring: Option<Spring>,
+    /// Hard bounds of the length, on top of the spring: a rope is a spring of frequency 0 with
+    /// a maximum length.
+    pub min_length: Option<f64>,
+    pub max_length: Option<f64>,
+    lambda: f64,
+    lambda_min: f64,
+    lambda_max: f64,
+}
+
+impl DistanceJoint {
+    pub fn new(
+        a: usize,
+        b: usize,
+        local_anchor_a: Vec2D,
+        local_anchor_b: Vec2D,
+        length: f64,
+    ) -> Self {
+        Self {
+            a,
+            b,
+            local_anchor_a,
+            local_anchor_b,
+            length,
+            spring: None,
+            min_length: None,
+            max_length: None,
+            lambda: 0.,
+            lambda_min: 0.,
+            lambda_max: 0.,
+        }
+    }
+
+    /// The current distance between the anchors of `a` and `b`.
+    pub fn current_length(&self, a: &RigidBody2D, b: &RigidBody2D) -> f64 {
+        (b.to_global(self.local_anchor_b) - a.to_global(self.local_anchor_a)).norm()
+    }
+}
+
+impl Joint for DistanceJoint {
+    fn bodies(&self) -> (usize, usize) {
+        (self.a, self.b)
+    }
+
+    fn reset(&mut self) {
+        self.lambda = 0.;
+        self.lambda_min = 0.;
+        self.lambda_max = 0.;
+    }
+
+    fn solve(
+        &mut self,
+        a: &mut RigidBody2D,
+        b: &mut RigidBody2D,
+        dt: f64,
+    ) -> Result<(), LinalgError> {
+        let anchor_a = a.to_global(self.local_anchor_a);
+        let anchor_b = b.to_global(self.local_anchor_b);
+
+        let offset = anchor_b - anchor_a;
+        let current = offset.norm();
+        // Any direction will do when the anchors meet.
+        let normal = if current > 0. {
+            offset / current
+        } else {
+            Vec2D::new(0., 1.)
+        };
+
+        let jacobian = linear_jacobian(a, b, anchor_a, anchor_b, normal);
+        let c = current - self.length;
+
+        let free = (f64::NEG_INFINITY, f64::INFINITY);
+        match self.spring {
+            None => solve_row(a, b, jacobian, free, &mut self.lambda, dt, |row| {
+                row.push_factor[(0, 0)] = -BAUMGARTE * c / dt;
+            })?,
+            Some(spring) if spring.frequency > 0. => {
+                solve_row(a, b, jacobian, free, &mut self.lambda, dt, |row| {
+                    row.set_spring(c, spring.frequency, spring.damping_ratio);
+                })?
+            }
+            Some(_) => (),
+        }
+
+        // Pushing the anchors apart, and pulling them together.
+        if let Some(min_length) = self.min_length {
+            let push = limit_push(current - min_length, true, dt);
+            let bounds = (0., f64::INFINITY);
+
+            solve_row(a, b, jacobian, bounds, &mut self.lambda_min, dt, |row| {
+                row.push_factor[(0, 0)] = push;
+            })?;
+        }
+
+        if let Some(max_length) = self.max_length {
+            let push = limit_push(current - max_length, false, dt);
+            let bounds = (f64::NEG_INFINITY, 0.);
+
+            solve_row(a, b, jacobian, bounds, &mut self.lambda_max, dt, |row| {
+                row.push_factor[(0, 0)] = push;
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::collider::Collider,
+        constraint::distance_joint::{DistanceJoint, Spring},
+        engine::Engine,
+        linalg::Vec2D,
+        rigidbody2d::{force_generator::GravityGenerator, RigidBody2D},
+    };
+
+    fn length(engine: &Engine) -> f64 {
+        (engine.bodies[1].position - engine.bodies[0].position).norm()
+    }
+
+    #[test]
+    fn example_one() {
+        // A pendulum hanging from a static pin, swinging down from the side
+        let pin = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let mut ball = RigidBody2D::new(Vec2D::new(100., 0.), Collider::circle(10.), 1.);
+        ball.add_force_generator(Box::new(GravityGenerator {}));
+
+        let joint = DistanceJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), 100.);
+        let mut engine = Engine::new(1000., 1000., vec![pin, ball]);
+        engine.add_joint(Box::new(joint)).unwrap();
+
+        let mut lowest = f64::NEG_INFINITY;
+        for _ in 0..200 {
+            engine.physics_step(16.).unwrap();
+            // Drifting a little, as only the velocities are solved
+            assert!((length(&engine) - 100.).abs() < 2.);
+            lowest = lowest.max(engine.bodies[1].position.y);
+        }
+
+        // The ball went through the bottom, and the pin didn't move.
+        assert!(lowest > 98.);
+        assert_eq!(engine.bodies[0].position, Vec2D::zero());
+        assert_eq!(engine.bodies[0].velocity, Vec2D::zero());
+    }
+
+    #[test]
+    fn example_two() {
+        // A ball pulled back to 100 from 150 by a critically damped spring
+        let pin = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let ball = RigidBody2D::new(Vec2D::new(150., 0.), Collider::circle(10.), 1.);
+
+        let mut joint = DistanceJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), 100.);
+        joint.spring = Some(Spring {
+            frequency: 1.,
+            damping_ratio: 1.,
+        });
+
+        let mut critical = Engine::new(1000., 1000., vec![pin.clone(), ball.clone()]);
+        critical.add_joint(Box::new(joint.clone())).unwrap();
+        for _ in 0..300 {
+            critical.physics_step(16.).unwrap();
+            // Without overshooting
+            assert!(length(&critical) > 100. - 1e-6);
+        }
+        assert!((length(&critical) - 100.).abs() < 0.1);
+
+        // Without damping, it goes past it.
+        joint.spring = Some(Spring {
+            frequency: 1.,
+            damping_ratio: 0.,
+        });
+        let mut bouncy = Engine::new(1000., 1000., vec![pin, ball]);
+        bouncy.add_joint(Box::new(joint)).unwrap();
+
+        let mut shortest = f64::INFINITY;
+        for _ in 0..60 {
+            bouncy.physics_step(16.).unwrap();
+            shortest = shortest.min(length(&bouncy));
+        }
+        assert!(shortest < 60.);
+    }
+
+    #[test]
+    fn example_three() {
+        // A rope: slack under 100, taut at 100
+        let pin = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let mut ball = RigidBody2D::new(Vec2D::new(50., 0.), Collider::circle(10.), 1.);
+        ball.velocity = Vec2D::new(200., 0.);
+
+        let mut joint = DistanceJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), 100.);
+        joint.spring = Some(Spring {
+            frequency: 0.,
+            damping_ratio: 0.,
+        });
+        joint.max_length = Some(100.);
+
+        let mut rope = Engine::new(1000., 1000., vec![pin.clone(), ball.clone()]);
+        rope.add_joint(Box::new(joint.clone())).unwrap();
+
+        // Flying freely for a while
+        rope.physics_step(100.).unwrap();
+        assert!((length(&rope) - 70.).abs() < 1e-9);
+
+        for _ in 0..20 {
+            rope.physics_step(16.).unwrap();
+            assert!(length(&rope) < 100. + 1e-6);
+        }
+        assert!(rope.bodies[1].velocity.x.abs() < 1e-6);
+
+        // A rod that can't get shorter than 80, with anchors off the centers
+        joint.max_length = None;
+        joint.min_length = Some(80.);
+        joint.local_anchor_b = Vec2D::new(-10., 0.);
+        ball.position = Vec2D::new(100., 0.);
+        ball.velocity = Vec2D::new(-200., 0.);
+
+        let mut rod = Engine::new(1000., 1000., vec![pin, ball]);
+        rod.add_joint(Box::new(joint)).unwrap();
+        for _ in 0..20 {
+            rod.physics_step(16.).unwrap();
+            assert!(length(&rod) > 90. - 1e-6);
+        }
+    }
+}
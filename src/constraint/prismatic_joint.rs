@@ -0,0 +1,326 @@
+use crate::{
+    collision::error::CollisionError,
+    constraint::{
+        limit_push, linear_jacobian, solve_row, Joint, Motor, ANGULAR_JACOBIAN, BAUMGARTE,
+    },
+    linalg::{LinalgError, Vec2D},
+    rigidbody2d::RigidBody2D,
+};
+
+/// Lets the anchor point on `b` slide along an axis going through the one on `a`, without the
+/// bodies turning relative to each other. The anchors and the axis are in the frame of their body.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrismaticJoint {
+    pub a: usize,
+    pub b: usize,
+    pub local_anchor_a: Vec2D,
+    pub local_anchor_b: Vec2D,
+    /// Unit vector, turning with `a`, set through `new` only.
+    local_axis_a: Vec2D,
+    /// The angle of `b` relative to `a` that is kept.
+    pub reference_angle: f64,
+    /// Bounds of the translation of the joint.
+    pub lower_translation: Option<f64>,
+    pub upper_translation: Option<f64>,
+    /// Moves `b` along the axis, relative to `a`.
+    pub motor: Option<Motor>,
+    lambda_perpendicular: f64,
+    lambda_angle: f64,
+    lambda_lower: f64,
+    lambda_upper: f64,
+    lambda_motor: f64,
+}
+
+impl PrismaticJoint {
+    /// Fails with `InvalidJoint` if `local_axis_a` is zero or not finite, as it gives no
+    /// direction to slide along.
+    pub fn new(
+        a: usize,
+        b: usize,
+        local_anchor_a: Vec2D,
+        local_anchor_b: Vec2D,
+        local_axis_a: Vec2D,
+    ) -> Result<Self, CollisionError> {
+        let norm = local_axis_a.norm();
+
+        if !norm.is_finite() || norm == 0. {
+            return Err(CollisionError::InvalidJoint);
+        }
+
+        Ok(Self {
+            a,
+            b,
+            local_anchor_a,
+            local_anchor_b,
+            local_axis_a: local_axis_a / norm,
+            reference_angle: 0.,
+            lower_translation: None,
+            upper_translation: None,
+            motor: None,
+            lambda_perpendicular: 0.,
+            lambda_angle: 0.,
+            lambda_lower: 0.,
+            lambda_upper: 0.,
+            lambda_motor: 0.,
+        })
+    }
+
+    /// The axis in the frame of `a`, of length 1.
+    pub fn local_axis_a(&self) -> Vec2D {
+        self.local_axis_a
+    }
+
+    /// The axis in world space.
+    pub fn axis(&self, a: &RigidBody2D) -> Vec2D {
+        a.to_global(self.local_axis_a) - a.position
+    }
+
+    /// How far the anchor of `b` is along the axis from the one of `a`.
+    pub fn translation(&self, a: &RigidBody2D, b: &RigidBody2D) -> f64 {
+        (b.to_global(self.local_anchor_b) - a.to_global(self.local_anchor_a)) * self.axis(a)
+    }
+}
+
+/// The jacobian of the offset between the anchors along `direction`, which turns with `a`.
+fn sliding_jacobian(
+    a: &RigidBody2D,
+    b: &RigidBody2D,
+    anchor_a: Vec2D,
+    anchor_b: Vec2D,
+    direction: Vec2D,
+) -> [f64; 6] {
+    let mut jacobian = linear_jacobian(a, b, anchor_a, anchor_b, direction);
+    jacobian[2] -= (anchor_b - anchor_a).cross(direction);
+
+    jacobian
+}
+
+impl Joint for PrismaticJoint {
+    fn bodies(&self) -> (usize, usize) {
+        (self.a, self.b)
+    }
+
+    fn reset(&mut self) {
+        self.lambda_perpendicular = 0.;
+        self.lambda_angle = 0.;
+        self.lambda_lower = 0.;
+        self.lambda_upper = 0.;
+        self.lambda_motor = 0.;
+    }
+
+    fn solve(
+        &mut self,
+        a: &mut RigidBody2D,
+        b: &mut RigidBody2D,
+        dt: f64,
+    ) -> Result<(), LinalgError> {
+        let anchor_a = a.to_global(self.local_anchor_a);
+        let anchor_b = b.to_global(self.local_anchor_b);
+        let offset = anchor_b - anchor_a;
+
+        let axis = self.axis(a);
+        let along = sliding_jacobian(a, b, anchor_a, anchor_b, axis);
+        let translation = offset * axis;
+
+        // Along the axis: the motor, then the limits stopping the slider at either end.
+        if let Some(motor) = self.motor {
+            let bounds = (-motor.max_force, motor.max_force);
+            let lambda = &mut self.lambda_motor;
+
+            solve_row(a, b, along, bounds, lambda, dt, |row| {
+                row.push_factor[(0, 0)] = motor.speed;
+            })?;
+        }
+
+        if let Some(lower_translation) = self.lower_translation {
+            let push = limit_push(translation - lower_translation, true, dt);
+            let bounds = (0., f64::INFINITY);
+            let lambda = &mut self.lambda_lower;
+
+            solve_row(a, b, along, bounds, lambda, dt, |row| {
+                row.push_factor[(0, 0)] = push;
+            })?;
+        }
+
+        if let Some(upper_translation) = self.upper_translation {
+            let push = limit_push(translation - upper_translation, false, dt);
+            let bounds = (f64::NEG_INFINITY, 0.);
+            let lambda = &mut self.lambda_upper;
+
+            solve_row(a, b, along, bounds, lambda, dt, |row| {
+                row.push_factor[(0, 0)] = push;
+            })?;
+        }
+
+        let free = (f64::NEG_INFINITY, f64::INFINITY);
+
+        let angle = b.angle - a.angle - self.reference_angle;
+        solve_row(
+            a,
+            b,
+            ANGULAR_JACOBIAN,
+            free,
+            &mut self.lambda_angle,
+            dt,
+            |row| {
+                row.push_factor[(0, 0)] = -BAUMGARTE * angle / dt;
+            },
+        )?;
+
+        let perpendicular = axis.left();
+        let across = sliding_jacobian(a, b, anchor_a, anchor_b, perpendicular);
+        let lambda = &mut self.lambda_perpendicular;
+
+        solve_row(a, b, across, free, lambda, dt, |row| {
+            row.push_factor[(0, 0)] = -BAUMGARTE * (offset * perpendicular) / dt;
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{collider::Collider, error::CollisionError},
+        constraint::{prismatic_joint::PrismaticJoint, Joint, Motor},
+        engine::Engine,
+        linalg::Vec2D,
+        rigidbody2d::{force_generator::GravityGenerator, RigidBody2D},
+    };
+
+    /// How far the anchor of `b` is from the axis of the joint.
+    fn off_axis(engine: &Engine, joint: &PrismaticJoint) -> f64 {
+        let (a, b) = joint.bodies();
+        let (a, b) = (&engine.bodies[a], &engine.bodies[b]);
+        let offset = b.to_global(joint.local_anchor_b) - a.to_global(joint.local_anchor_a);
+
+        offset.cross(joint.axis(a)).abs()
+    }
+
+    #[test]
+    fn example_one() {
+        // A box sliding down a slope, stopped 50 further
+        let frame = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let mut slider = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(10., 10.), 1.);
+        slider.add_force_generator(Box::new(GravityGenerator {}));
+        // Spun, but kept from turning
+        slider.angular_velocity = 5.;
+
+        let mut joint =
+            PrismaticJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), Vec2D::new(1., 1.)).unwrap();
+        joint.upper_translation = Some(50.);
+
+        let mut engine = Engine::new(1000., 1000., vec![frame, slider]);
+        engine.add_joint(Box::new(joint.clone())).unwrap();
+
+        for _ in 0..100 {
+            engine.physics_step(16.).unwrap();
+            assert!(off_axis(&engine, &joint) < 1.);
+            assert!(engine.bodies[1].angle.abs() < 1e-2);
+            assert!(joint.translation(&engine.bodies[0], &engine.bodies[1]) < 51.);
+        }
+
+        let translation = joint.translation(&engine.bodies[0], &engine.bodies[1]);
+        assert!((translation - 50.).abs() < 1.);
+        assert!(engine.bodies[1].velocity.norm() < 1e-6);
+    }
+
+    #[test]
+    fn example_two() {
+        // An elevator lifted by a strong motor, against gravity
+        let shaft = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let mut cabin = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(20., 20.), 1.);
+        cabin.add_force_generator(Box::new(GravityGenerator {}));
+
+        let mut joint =
+            PrismaticJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), Vec2D::new(0., -1.)).unwrap();
+        joint.motor = Some(Motor {
+            speed: 20.,
+            max_force: 1e6,
+        });
+        joint.upper_translation = Some(100.);
+
+        let mut strong = Engine::new(1000., 1000., vec![shaft.clone(), cabin.clone()]);
+        strong.add_joint(Box::new(joint.clone())).unwrap();
+        strong.physics_step(16.).unwrap();
+        assert!((strong.bodies[1].velocity - Vec2D::new(0., -20.)).near_zero());
+
+        // Until the top of the shaft
+        for _ in 0..400 {
+            strong.physics_step(16.).unwrap();
+        }
+        let translation = joint.translation(&strong.bodies[0], &strong.bodies[1]);
+        assert!((translation - 100.).abs() < 1.);
+
+        // Too weak to hold the cabin
+        joint.motor = Some(Motor {
+            speed: 20.,
+            max_force: 500.,
+        });
+        let mut weak = Engine::new(1000., 1000., vec![shaft, cabin]);
+        weak.add_joint(Box::new(joint)).unwrap();
+        weak.physics_step(16.).unwrap();
+
+        let expected = (981. - 500.) * 0.016;
+        assert!((weak.bodies[1].velocity - Vec2D::new(0., expected)).near_zero());
+    }
+
+    #[test]
+    fn example_three() {
+        // A sliding door on a moving carriage, pushed back past its lower limit
+        let carriage = RigidBody2D::new(Vec2D::zero(), Collider::rectangle(100., 10.), 10.);
+        let mut door = RigidBody2D::new(Vec2D::new(0., 30.), Collider::rectangle(40., 50.), 1.);
+        door.velocity = Vec2D::new(-100., 0.);
+
+        let mut joint = PrismaticJoint::new(
+            0,
+            1,
+            Vec2D::zero(),
+            Vec2D::new(0., -30.),
+            Vec2D::new(1., 0.),
+        )
+        .unwrap();
+        joint.lower_translation = Some(-20.);
+
+        let mut engine = Engine::new(1000., 1000., vec![carriage, door]);
+        engine.add_joint(Box::new(joint.clone())).unwrap();
+
+        for _ in 0..30 {
+            engine.physics_step(16.).unwrap();
+            assert!(joint.translation(&engine.bodies[0], &engine.bodies[1]) > -21.);
+            assert!(off_axis(&engine, &joint) < 1.);
+        }
+
+        // The door dragged the carriage along, the pair spinning a little as it was pulled above
+        // its center, and momentum is kept.
+        let translation = joint.translation(&engine.bodies[0], &engine.bodies[1]);
+        assert!((translation + 20.).abs() < 1.);
+
+        let (carriage, door) = (&engine.bodies[0], &engine.bodies[1]);
+        assert!(carriage.velocity.x < 0.);
+        assert!((carriage.angular_velocity - door.angular_velocity).abs() < 1e-3);
+
+        let momentum = carriage.velocity * 10. + door.velocity;
+        assert!((momentum - Vec2D::new(-100., 0.)).near_zero());
+    }
+
+    #[test]
+    fn example_four() {
+        // The axis is kept of length 1.
+        let joint =
+            PrismaticJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), Vec2D::new(3., 4.)).unwrap();
+        assert!((joint.local_axis_a() - Vec2D::new(0.6, 0.8)).near_zero());
+    }
+
+    #[test]
+    fn example_five() {
+        // Without any direction to slide along
+        for axis in [Vec2D::zero(), Vec2D::new(f64::NAN, 1.)] {
+            assert_eq!(
+                PrismaticJoint::new(0, 1, Vec2D::zero(), Vec2D::zero(), axis),
+                Err(CollisionError::InvalidJoint)
+            );
+        }
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    constraint::{
+        limit_push, linear_jacobian, solve_row, Joint, Motor, ANGULAR_JACOBIAN, BAUMGARTE,
+    },
+    linalg::{LinalgError, Vec2D},
+    rigidbody2d::RigidBody2D,
+};
+
+/// Pins an anchor point on `a` to one on `b`, both in the frame of their body, leaving them free
+/// to turn around it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RevoluteJoint {
+    pub a: usize,
+    pub b: usize,
+    pub local_anchor_a: Vec2D,
+    pub local_anchor_b: Vec2D,
+    /// The angle of `b` relative to `a` at which the joint is at 0.
+    pub reference_angle: f64,
+    /// Bounds of the angle of the joint.
+    pub lower_angle: Option<f64>,
+    pub upper_angle: Option<f64>,
+    /// Turns `b` relative to `a`, counterclockwise for a positive speed.
+    pub motor: Option<Motor>,
+    lambda_x: f64,
+    lambda_y: f64,
+    lambda_lower: f64,
+    lambda_upper: f64,
+    lambda_motor: f64,
+}
+
+impl RevoluteJoint {
+    pub fn new(a: usize, b: usize, local_anchor_a: Vec2D, local_anchor_b: Vec2D) -> Self {
+        Self {
+            a,
+            b,
+            local_anchor_a,
+            local_anchor_b,
+            reference_angle: 0.,
+            lower_angle: None,
+            upper_angle: None,
+            motor: None,
+            lambda_x: 0.,
+            lambda_y: 0.,
+            lambda_lower: 0.,
+            lambda_upper: 0.,
+            lambda_motor: 0.,
+        }
+    }
+
+    /// The current angle of the joint.
+    pub fn angle(&self, a: &RigidBody2D, b: &RigidBody2D) -> f64 {
+        b.angle - a.angle - self.reference_angle
+    }
+}
+
+impl Joint for RevoluteJoint {
+    fn bodies(&self) -> (usize, usize) {
+        (self.a, self.b)
+    }
+
+    fn reset(&mut self) {
+        self.lambda_x = 0.;
+        self.lambda_y = 0.;
+        self.lambda_lower = 0.;
+        self.lambda_upper = 0.;
+        self.lambda_motor = 0.;
+    }
+
+    fn solve(
+        &mut self,
+        a: &mut RigidBody2D,
+        b: &mut RigidBody2D,
+        dt: f64,
+    ) -> Result<(), LinalgError> {
+        let angle = self.angle(a, b);
+
+        // Turned by the motor first, `b` is then held back by a limit it went past.
+        if let Some(motor) = self.motor {
+            let bounds = (-motor.max_force, motor.max_force);
+
+            solve_row(
+                a,
+                b,
+                ANGULAR_JACOBIAN,
+                bounds,
+                &mut self.lambda_motor,
+                dt,
+                |row| {
+                    row.push_factor[(0, 0)] = motor.speed;
+                },
+            )?;
+        }
+
+        if let Some(lower_angle) = self.lower_angle {
+            let push = limit_push(angle - lower_angle, true, dt);
+            let bounds = (0., f64::INFINITY);
+
+            solve_row(
+                a,
+                b,
+                ANGULAR_JACOBIAN,
+                bounds,
+                &mut self.lambda_lower,
+                dt,
+                |row| {
+                    row.push_factor[(0, 0)] = push;
+                },
+            )?;
+        }
+
+        if let Some(upper_angle) = self.upper_angle {
+            let push = limit_push(angle - upper_angle, false, dt);
+            let bounds = (f64::NEG_INFINITY, 0.);
+
+            solve_row(
+                a,
+                b,
+                ANGULAR_JACOBIAN,
+                bounds,
+                &mut self.lambda_upper,
+                dt,
+                |row| {
+                    row.push_factor[(0, 0)] = push;
+                },
+            )?;
+        }
+
+        let anchor_a = a.to_global(self.local_anchor_a);
+        let anchor_b = b.to_global(self.local_anchor_b);
+        let offset = anchor_b - anchor_a;
+        let free = (f64::NEG_INFINITY, f64::INFINITY);
+
+        for (axis, lambda) in [
+            (Vec2D::new(1., 0.), &mut self.lambda_x),
+            (Vec2D::new(0., 1.), &mut self.lambda_y),
+        ] {
+            let jacobian = linear_jacobian(a, b, anchor_a, anchor_b, axis);
+
+            solve_row(a, b, jacobian, free, lambda, dt, |row| {
+                row.push_factor[(0, 0)] = -BAUMGARTE * (offset * axis) / dt;
+            })?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::collider::Collider,
+        constraint::{revolute_joint::RevoluteJoint, Joint, Motor},
+        engine::Engine,
+        linalg::Vec2D,
+        rigidbody2d::{force_generator::GravityGenerator, RigidBody2D},
+    };
+
+    fn anchor_gap(engine: &Engine, joint: &RevoluteJoint) -> f64 {
+        let (a, b) = joint.bodies();
+        let (a, b) = (&engine.bodies[a], &engine.bodies[b]);
+
+        (b.to_global(joint.local_anchor_b) - a.to_global(joint.local_anchor_a)).norm()
+    }
+
+    #[test]
+    fn example_one() {
+        // A bar hanging from a static pin by one end, swinging down from the side
+        let pin = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let mut bar = RigidBody2D::new(Vec2D::new(50., 0.), Collider::rectangle(100., 10.), 1.);
+        bar.add_force_generator(Box::new(GravityGenerator {}));
+
+        let joint = RevoluteJoint::new(0, 1, Vec2D::zero(), Vec2D::new(-50., 0.));
+        let mut engine = Engine::new(1000., 1000., vec![pin, bar]);
+        engine.add_joint(Box::new(joint.clone())).unwrap();
+
+        let mut lowest = f64::NEG_INFINITY;
+        for _ in 0..200 {
+            engine.physics_step(16.).unwrap();
+            // The end of the bar wanders a little off the pin as it swings faster
+            assert!(anchor_gap(&engine, &joint) < 2.);
+            lowest = lowest.max(engine.bodies[1].position.y);
+        }
+
+        // The bar went through the bottom, turning around the pin.
+        assert!(lowest > 48.);
+        assert_eq!(engine.bodies[0].position, Vec2D::zero());
+    }
+
+    #[test]
+    fn example_two() {
+        // A wheel on a static axle, driven by a strong motor
+        let axle = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let wheel = RigidBody2D::new(Vec2D::zero(), Collider::circle(10.), 1.);
+
+        let mut joint = RevoluteJoint::new(0, 1, Vec2D::zero(), Vec2D::zero());
+        joint.motor = Some(Motor {
+            speed: 2.,
+            max_force: 1e6,
+        });
+
+        let mut strong = Engine::new(1000., 1000., vec![axle.clone(), wheel.clone()]);
+        strong.add_joint(Box::new(joint.clone())).unwrap();
+        strong.physics_step(16.).unwrap();
+        assert!((strong.bodies[1].angular_velocity - 2.).abs() < 1e-9);
+        assert!(strong.bodies[1].position.near_zero());
+
+        // A weak one only gets there slowly, at its max torque.
+        joint.motor = Some(Motor {
+            speed: 2.,
+            max_force: 10.,
+        });
+        let mut weak = Engine::new(1000., 1000., vec![axle, wheel.clone()]);
+        weak.add_joint(Box::new(joint)).unwrap();
+        weak.physics_step(16.).unwrap();
+
+        let expected = 10. * wheel.get_inertia_inv() * 0.016;
+        assert!((weak.bodies[1].angular_velocity - expected).abs() < 1e-9);
+    }
+
+    #[test]
+    fn example_three() {
+        // A door hinged at one end, falling until its upper limit
+        let frame = RigidBody2D::new(Vec2D::zero(), Collider::circle(1.), 0.);
+        let mut door = RigidBody2D::new(Vec2D::new(50., 0.), Collider::rectangle(100., 10.), 1.);
+        door.add_force_generator(Box::new(GravityGenerator {}));
+
+        let mut joint = RevoluteJoint::new(0, 1, Vec2D::zero(), Vec2D::new(-50., 0.));
+        joint.lower_angle = Some(-0.5);
+        joint.upper_angle = Some(0.5);
+
+        let mut engine = Engine::new(1000., 1000., vec![frame, door]);
+        engine.add_joint(Box::new(joint.clone())).unwrap();
+
+        for _ in 0..100 {
+            engine.physics_step(16.).unwrap();
+            let angle = joint.angle(&engine.bodies[0], &engine.bodies[1]);
+            assert!((-0.5..0.52).contains(&angle));
+        }
+
+        let angle = joint.angle(&engine.bodies[0], &engine.bodies[1]);
+        assert!((angle - 0.5).abs() < 0.02);
+        assert!(anchor_gap(&engine, &joint) < 1.);
+    }
+}
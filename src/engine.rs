@@ -3,45 +3,126 @@ use web_sys::CanvasRenderingContext2d;
 
 use crate::{
     collision::{
-        collider::Collider::{self, CircleCollider,PolygonCollider},
-        algorithms::
-            gjk::gjk_collision
-        ,
-        manifold::ContactManifold,
+        aabb::Aabb,
+        algorithms::{
+            gjk::{gjk_collision, shape_cast},
+            segment::{capsule_segment, chain_segments},
+        },
+        collider::Collider::{
+            self, CapsuleCollider, ChainCollider, CircleCollider, CompoundCollider, ConvexCollider,
+            EllipseCollider, HeightfieldCollider, PolygonCollider, SegmentCollider,
+        },
+        distance::convex_parts,
+        error::CollisionError,
+        manifold::{ContactManifold, PenetrationAlgorithm},
+        ray::{Ray, RayHit, RaycastMode},
+        support_map::support_polygon,
     },
+    constraint::Joint,
     linalg::Vec2D,
-    render::{circle, line, polygon, rect},
+    render::{capsule, circle, ellipse, line, polygon, rect, rounded_polygon},
     rigidbody2d::{force_generator::GravityGenerator, RigidBody2D},
 };
 
-// #[wasm_bindgen]
-// extern "C" {
-//     #[wasm_bindgen(js_namespace = console)]
-//     fn log(s: &str);
-// }
+#[cfg(target_arch = "wasm32")]
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_namespace = console)]
+    fn log(s: &str);
+}
+
+/// The console only exists in the browser: natively, logs go to the standard error.
+#[cfg(not(target_arch = "wasm32"))]
+fn log(s: &str) {
+    eprintln!("{s}");
+}
 
-// macro_rules! clg {
-//     ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
-// }
+macro_rules! clg {
+    ($($t:tt)*) => (log(&format_args!($($t)*).to_string()))
+}
+
+/// What the engine does with a pair of bodies it fails to collide, see `CollisionError`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ErrorPolicy {
+    /// The pair is left out, as if the bodies didn't touch.
+    #[default]
+    Skip,
+    /// The pair is left out, and the error is written to the console, or to the standard error
+    /// outside of the browser.
+    Log,
+    /// The query stops and returns the error.
+    Abort,
+}
 
 pub struct Engine {
     pub width: f64,
     pub height: f64,
     pub bodies: Vec<RigidBody2D>,
+    pub error_policy: ErrorPolicy,
+    pub penetration: PenetrationAlgorithm,
+    pub joints: Vec<Box<dyn Joint>>,
+}
+
+/// How many times the joints are solved at each step.
+const JOINT_ITERATIONS: usize = 10;
+
+/// Two distinct bodies of `bodies`, mutably. `None` when `a` and `b` are the same or out of range.
+fn body_pair(
+    bodies: &mut [RigidBody2D],
+    a: usize,
+    b: usize,
+) -> Option<(&mut RigidBody2D, &mut RigidBody2D)> {
+    if a == b || a.max(b) >= bodies.len() {
+        return None;
+    }
+
+    if a < b {
+        let (left, right) = bodies.split_at_mut(b);
+        Some((&mut left[a], &mut right[0]))
+    } else {
+        let (left, right) = bodies.split_at_mut(a);
+        Some((&mut right[0], &mut left[b]))
+    }
+}
+
+impl ErrorPolicy {
+    /// Applies the policy to the result of a query on a pair of bodies: `None` when the pair is
+    /// left out.
+    fn handle<T>(self, result: Result<T, CollisionError>) -> Result<Option<T>, CollisionError> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(err) => match self {
+                ErrorPolicy::Skip => Ok(None),
+                ErrorPolicy::Log => {
+                    clg!("Skipping a pair of bodies: {err}");
+                    Ok(None)
+                }
+                ErrorPolicy::Abort => Err(err),
+            },
+        }
+    }
 }
 
 impl Engine {
+    /// An engine without joints, with the default error policy and penetration algorithm.
+    pub fn new(width: f64, height: f64, bodies: Vec<RigidBody2D>) -> Self {
+        Engine {
+            width,
+            height,
+            bodies,
+            error_policy: ErrorPolicy::default(),
+            penetration: PenetrationAlgorithm::default(),
+            joints: vec![],
+        }
+    }
+
     pub fn demo_circle(width: f64, height: f64) -> Self {
         let circle_collider = CircleCollider { radius: 100. };
         let mut circle1 = RigidBody2D::new(Vec2D::new(width / 2., 0.), circle_collider, 1.);
 
         circle1.add_force_generator(Box::new(GravityGenerator {}));
 
-        Engine {
-            width,
-            height,
-            bodies: vec![circle1],
-        }
+        Engine::new(width, height, vec![circle1])
     }
 
     pub fn demo_rectangle(width: f64, height: f64) -> Self {
@@ -51,11 +132,7 @@ impl Engine {
 
         rectangle1.angular_velocity = 0.1;
 
-        Engine {
-            width,
-            height,
-            bodies: vec![rectangle1],
-        }
+        Engine::new(width, height, vec![rectangle1])
     }
 
     pub fn demo_collide(width: f64, height: f64) -> Self {
@@ -74,11 +151,7 @@ impl Engine {
         // rectangle1.angle = std::f64::consts::PI;
         // rectangle1.angular_velocity = 0.3;
 
-        Engine {
-            width,
-            height,
-            bodies: vec![rectangle1, rectangle2],
-        }
+        Engine::new(width, height, vec![rectangle1, rectangle2])
     }
 
     pub fn demo_collide_circle(width: f64, height: f64) -> Self {
@@ -89,18 +162,262 @@ impl Engine {
 
         // circle1.add_force_generator(Box::new(GravityGenerator {}));
 
-        Engine {
-            width,
-            height,
-            bodies: vec![circle1, circle2],
+        Engine::new(width, height, vec![circle1, circle2])
+    }
+
+    /// Applies `error_policy` to the result of a query on a pair of bodies: `None` when the pair
+    /// is left out.
+    fn handle_error<T>(
+        &self,
+        result: Result<T, CollisionError>,
+    ) -> Result<Option<T>, CollisionError> {
+        self.error_policy.handle(result)
+    }
+
+    /// Adds `joint`, failing with `InvalidJoint` when it doesn't tie two different bodies of the
+    /// engine.
+    pub fn add_joint(&mut self, joint: Box<dyn Joint>) -> Result<(), CollisionError> {
+        let (a, b) = joint.bodies();
+
+        if a == b || a.max(b) >= self.bodies.len() {
+            return Err(CollisionError::InvalidJoint);
         }
+
+        self.joints.push(joint);
+        Ok(())
     }
 
-    /// dt: in ms
-    pub fn physics_step(&mut self, dt: f64) {
+    /// dt: in ms. Only fails when a joint does with `error_policy` at `Abort`, before the bodies
+    /// are moved.
+    pub fn physics_step(&mut self, dt: f64) -> Result<(), CollisionError> {
+        let dt = dt / 1000.;
+
+        for body in self.bodies.iter_mut() {
+            body.integrate_velocity(dt);
+        }
+
+        self.solve_joints(dt)?;
+
         for body in self.bodies.iter_mut() {
-            body.step(dt / 1000.);
+            body.integrate_position(dt);
+        }
+
+        Ok(())
+    }
+
+    /// Corrects the velocities of the bodies tied by joints. dt: in s
+    fn solve_joints(&mut self, dt: f64) -> Result<(), CollisionError> {
+        let policy = self.error_policy;
+        // A joint failing once is left out of the rest of the step, e.g. one between two static
+        // bodies, which has nothing to move.
+        let mut failed = vec![false; self.joints.len()];
+
+        for joint in self.joints.iter_mut() {
+            joint.reset();
+        }
+
+        for _ in 0..JOINT_ITERATIONS {
+            for (index, joint) in self.joints.iter_mut().enumerate() {
+                if failed[index] {
+                    continue;
+                }
+
+                let (a, b) = joint.bodies();
+                let result = match body_pair(&mut self.bodies, a, b) {
+                    Some((a, b)) => joint.solve(a, b, dt).map_err(CollisionError::from),
+                    None => Err(CollisionError::InvalidJoint),
+                };
+
+                if policy.handle(result)?.is_none() {
+                    failed[index] = true;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Casts `ray` against every body accepted by `filter`, and returns the index of the bodies
+    /// hit along with the hit itself, according to `mode`.
+    pub fn raycast<F>(
+        &self,
+        ray: &Ray,
+        mode: RaycastMode,
+        filter: F,
+    ) -> Result<Vec<(usize, RayHit)>, CollisionError>
+    where
+        F: Fn(usize, &RigidBody2D) -> bool,
+    {
+        let mut ray = *ray;
+        let mut hits = vec![];
+
+        for (index, body) in self.bodies.iter().enumerate() {
+            if !filter(index, body) {
+                continue;
+            }
+
+            // Cheap rejection before testing the actual shape.
+            if body
+                .world_aabb()
+                .raycast(ray.origin, ray.direction, ray.max_fraction)
+                .is_none()
+            {
+                continue;
+            }
+
+            if let Some(Some(hit)) = self.handle_error(body.raycast(&ray))? {
+                match mode {
+                    RaycastMode::Any => return Ok(vec![(index, hit)]),
+                    RaycastMode::Closest => {
+                        // Further bodies can only be hit before this one.
+                        ray.max_fraction = hit.fraction;
+                        hits = vec![(index, hit)];
+                    }
+                    RaycastMode::All => hits.push((index, hit)),
+                }
+            }
         }
+
+        hits.sort_by(|(_, a), (_, b)| a.fraction.total_cmp(&b.fraction));
+
+        Ok(hits)
+    }
+
+    /// Moves `collider`, placed at `position` with `angle`, along `translation` and returns the
+    /// first body accepted by `filter` it hits, with the fraction of `translation` travelled.
+    pub fn shape_cast<F>(
+        &self,
+        collider: &Collider,
+        position: Vec2D,
+        angle: f64,
+        translation: Vec2D,
+        filter: F,
+    ) -> Result<Option<(usize, RayHit)>, CollisionError>
+    where
+        F: Fn(usize, &RigidBody2D) -> bool,
+    {
+        let mut caster = RigidBody2D::new(position, collider.clone(), 1.);
+        caster.angle = angle;
+
+        let start = caster.world_aabb();
+        let swept = start.union(&start.translate(translation));
+
+        let mut closest: Option<(usize, RayHit)> = None;
+        // Chains, compounds and heightfields would be cast as their hull.
+        let Some(caster_parts) = self.handle_error(convex_parts(&caster, None))? else {
+            return Ok(None);
+        };
+
+        for (index, body) in self.bodies.iter().enumerate() {
+            if !filter(index, body) || !swept.overlaps(&body.world_aabb()) {
+                continue;
+            }
+
+            let Some(parts) = self.handle_error(convex_parts(body, Some(&swept)))? else {
+                continue;
+            };
+
+            for part in parts {
+                if !swept.overlaps(&part.world_aabb()) {
+                    continue;
+                }
+
+                for caster_part in caster_parts.iter() {
+                    let result = shape_cast(caster_part, &part, translation);
+
+                    if let Some(Some(hit)) = self.handle_error(result)? {
+                        let is_closer = match closest {
+                            Some((_, best)) => hit.fraction < best.fraction,
+                            None => true,
+                        };
+
+                        if is_closer {
+                            closest = Some((index, hit));
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(closest)
+    }
+
+    /// Indices of the bodies accepted by `filter` containing `point`.
+    pub fn query_point<F>(&self, point: Vec2D, filter: F) -> Vec<usize>
+    where
+        F: Fn(usize, &RigidBody2D) -> bool,
+    {
+        self.bodies
+            .iter()
+            .enumerate()
+            .filter(|(index, body)| filter(*index, body))
+            .filter(|(_, body)| body.world_aabb().contains_point(point))
+            .filter(|(_, body)| body.contains_point(point))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Indices of the bodies accepted by `filter` whose shape overlaps `aabb`.
+    pub fn query_aabb<F>(&self, aabb: &Aabb, filter: F) -> Result<Vec<usize>, CollisionError>
+    where
+        F: Fn(usize, &RigidBody2D) -> bool,
+    {
+        let size = aabb.max - aabb.min;
+
+        self.query_collider(
+            &Collider::rectangle(size.x, size.y),
+            aabb.center(),
+            0.,
+            filter,
+        )
+    }
+
+    /// Indices of the bodies accepted by `filter` overlapping `collider`,
+    /// placed at `position` with `angle`.
+    pub fn query_collider<F>(
+        &self,
+        collider: &Collider,
+        position: Vec2D,
+        angle: f64,
+        filter: F,
+    ) -> Result<Vec<usize>, CollisionError>
+    where
+        F: Fn(usize, &RigidBody2D) -> bool,
+    {
+        let mut test_body = RigidBody2D::new(position, collider.clone(), 1.);
+        test_body.angle = angle;
+
+        let aabb = test_body.world_aabb();
+        let mut indices = vec![];
+        // Chains, compounds and heightfields would be tested as their hull.
+        let Some(test_parts) = self.handle_error(convex_parts(&test_body, None))? else {
+            return Ok(indices);
+        };
+
+        for (index, body) in self.bodies.iter().enumerate() {
+            if !filter(index, body) || !aabb.overlaps(&body.world_aabb()) {
+                continue;
+            }
+
+            let Some(parts) = self.handle_error(convex_parts(body, Some(&aabb)))? else {
+                continue;
+            };
+
+            'parts: for part in parts {
+                if !aabb.overlaps(&part.world_aabb()) {
+                    continue;
+                }
+
+                for test_part in test_parts.iter() {
+                    if let Some(Some(_)) = self.handle_error(gjk_collision(test_part, &part))? {
+                        indices.push(index);
+                        break 'parts;
+                    }
+                }
+            }
+        }
+
+        Ok(indices)
     }
 
     pub fn draw(&self, ctx: &CanvasRenderingContext2d) -> Result<(), JsValue> {
@@ -108,8 +425,12 @@ impl Engine {
             let a = &self.bodies[0];
             let b = &self.bodies[1];
 
-            match gjk_collision(a, b) {
-                Some(_) => {
+            let overlap = self
+                .handle_error(gjk_collision(a, b))
+                .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+            match overlap {
+                Some(Some(_)) => {
                     rect(
                         &ctx,
                         Vec2D::zero(),
@@ -122,38 +443,7 @@ impl Engine {
         }
 
         for body in self.bodies.iter() {
-            match &body.collider {
-                CircleCollider { radius } => {
-                    ctx.set_fill_style(&"red".into());
-                    ctx.set_stroke_style(&"black".into());
-                    ctx.begin_path();
-
-                    ctx.arc(
-                        body.position.x,
-                        body.position.y,
-                        *radius,
-                        0.,
-                        std::f64::consts::PI * 2.,
-                    )?;
-
-                    ctx.fill();
-                    ctx.stroke();
-                }
-                PolygonCollider { vertices } => {
-                    // let mut world_verticies = vec![];
-
-                    // for vertex in vertices {
-                    //     world_verticies.push(body.to_global(*vertex));
-                    // }
-
-                    let world_verticies: Vec<Vec2D> =
-                        vertices.iter().map(|v| body.to_global(*v)).collect();
-
-                    polygon(ctx, &world_verticies, "black");
-
-                    circle(ctx, body.position, 10., "red")?;
-                }
-            }
+            draw_body(ctx, body)?;
         }
 
         if self.bodies.len() == 2 {
@@ -161,13 +451,15 @@ impl Engine {
             let b = &self.bodies[1].clone();
 
             let mut manifold = ContactManifold::new(a, b);
-            manifold.compute();
+            manifold.penetration = self.penetration;
+            self.handle_error(manifold.compute())
+                .map_err(|err| JsValue::from_str(&err.to_string()))?;
 
             if let Some(normal) = manifold.normal {
-                for (point, depth) in manifold.points {
-                    circle(&ctx, point, 5., "green")?;
+                for contact in manifold.points {
+                    circle(&ctx, contact.point, 5., "green")?;
                     
-                    line(&ctx, point, point + normal * depth, "green");
+                    line(&ctx, contact.on_b(normal), contact.on_a(normal), "green");
                 }
             }
 
@@ -309,3 +601,363 @@ impl Engine {
         Ok(())
     }
 }
+
+fn draw_body(ctx: &CanvasRenderingContext2d, body: &RigidBody2D) -> Result<(), JsValue> {
+    match &body.collider {
+        CircleCollider { radius } => {
+            ctx.set_fill_style(&"red".into());
+            ctx.set_stroke_style(&"black".into());
+            ctx.begin_path();
+
+            ctx.arc(
+                body.position.x,
+                body.position.y,
+                *radius,
+                0.,
+                std::f64::consts::PI * 2.,
+            )?;
+
+            ctx.fill();
+            ctx.stroke();
+        }
+        PolygonCollider { vertices, radius } => {
+            // let mut world_verticies = vec![];
+
+            // for vertex in vertices {
+            //     world_verticies.push(body.to_global(*vertex));
+            // }
+
+            let world_verticies: Vec<Vec2D> = vertices.iter().map(|v| body.to_global(*v)).collect();
+
+            if *radius > 0. {
+                rounded_polygon(ctx, &world_verticies, *radius, "black")?;
+            } else {
+                polygon(ctx, &world_verticies, "black");
+            }
+
+            circle(ctx, body.position, 10., "red")?;
+        }
+        EllipseCollider {
+            half_width,
+            half_height,
+        } => {
+            ellipse(
+                ctx,
+                body.position,
+                *half_width,
+                *half_height,
+                body.angle,
+                "black",
+            )?;
+
+            circle(ctx, body.position, 10., "red")?;
+        }
+        ConvexCollider { shape } => {
+            let world_vertices: Vec<Vec2D> = support_polygon(&**shape)
+                .iter()
+                .map(|v| body.to_global(*v))
+                .collect();
+
+            polygon(ctx, &world_vertices, "black");
+
+            circle(ctx, body.position, 10., "red")?;
+        }
+        CapsuleCollider { .. } => {
+            let (p1, p2, radius) =
+                capsule_segment(body).map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+            capsule(ctx, p1, p2, radius, "black")?;
+
+            circle(ctx, body.position, 10., "red")?;
+        }
+        SegmentCollider { .. } | ChainCollider { .. } | HeightfieldCollider { .. } => {
+            let segments =
+                chain_segments(body).map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+            for segment in segments {
+                line(ctx, segment.start, segment.end, "black");
+            }
+        }
+        CompoundCollider { .. } => {
+            for (_, child) in body.child_bodies() {
+                draw_body(ctx, &child)?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        collision::{
+            aabb::Aabb,
+            collider::{Collider, CompoundChild},
+            error::CollisionError,
+            ray::{Ray, RaycastMode},
+            support_map::SupportMap,
+        },
+        constraint::distance_joint::DistanceJoint,
+        engine::{Engine, ErrorPolicy},
+        linalg::Vec2D,
+        rigidbody2d::RigidBody2D,
+    };
+
+    fn row_of_circles() -> Engine {
+        let bodies = vec![
+            RigidBody2D::new(Vec2D::new(30., 0.), Collider::circle(1.), 1.),
+            RigidBody2D::new(Vec2D::new(10., 0.), Collider::circle(1.), 1.),
+            RigidBody2D::new(Vec2D::new(20., 0.), Collider::rectangle(2., 2.), 1.),
+            RigidBody2D::new(Vec2D::new(20., 10.), Collider::circle(1.), 1.),
+        ];
+
+        Engine::new(100., 100., bodies)
+    }
+
+    #[test]
+    fn raycast() {
+        let engine = row_of_circles();
+        let ray = Ray::new(Vec2D::zero(), Vec2D::new(1., 0.), 100.);
+
+        let closest = engine
+            .raycast(&ray, RaycastMode::Closest, |_, _| true)
+            .unwrap();
+        assert_eq!(closest.len(), 1);
+        assert_eq!(closest[0].0, 1);
+        assert_eq!(closest[0].1.point, Vec2D::new(9., 0.));
+
+        let all = engine.raycast(&ray, RaycastMode::All, |_, _| true).unwrap();
+        let indices: Vec<usize> = all.iter().map(|(index, _)| *index).collect();
+        assert_eq!(indices, vec![1, 2, 0]);
+
+        let any = engine.raycast(&ray, RaycastMode::Any, |_, _| true).unwrap();
+        assert_eq!(any.len(), 1);
+
+        let filtered = engine
+            .raycast(&ray, RaycastMode::Closest, |index, _| index != 1)
+            .unwrap();
+        assert_eq!(filtered[0].0, 2);
+        assert_eq!(filtered[0].1.point, Vec2D::new(19., 0.));
+
+        let short = Ray::new(Vec2D::zero(), Vec2D::new(1., 0.), 5.);
+        assert!(engine
+            .raycast(&short, RaycastMode::All, |_, _| true)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn shape_cast() {
+        let engine = row_of_circles();
+        let collider = Collider::circle(2.);
+
+        let (index, hit) = engine
+            .shape_cast(&collider, Vec2D::zero(), 0., Vec2D::new(40., 0.), |_, _| {
+                true
+            })
+            .unwrap()
+            .unwrap();
+        assert_eq!(index, 1);
+        assert!((hit.fraction * 40. - 7.).abs() < 1e-2);
+
+        let (index, _) = engine
+            .shape_cast(
+                &collider,
+                Vec2D::zero(),
+                0.,
+                Vec2D::new(40., 0.),
+                |index, _| index != 1,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(index, 2);
+
+        assert!(engine
+            .shape_cast(&collider, Vec2D::zero(), 0., Vec2D::new(0., 40.), |_, _| {
+                true
+            })
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn queries() {
+        let engine = row_of_circles();
+
+        assert_eq!(
+            engine.query_point(Vec2D::new(10.5, 0.5), |_, _| true),
+            vec![1]
+        );
+        assert_eq!(
+            engine.query_point(Vec2D::new(20.9, 0.9), |_, _| true),
+            vec![2]
+        );
+        assert!(engine
+            .query_point(Vec2D::new(10.9, 0.9), |_, _| true)
+            .is_empty());
+
+        let aabb = Aabb::new(Vec2D::new(9.5, -5.), Vec2D::new(20., 20.));
+        assert_eq!(engine.query_aabb(&aabb, |_, _| true), Ok(vec![1, 2, 3]));
+        assert_eq!(
+            engine.query_aabb(&aabb, |index, _| index != 2),
+            Ok(vec![1, 3])
+        );
+
+        // Explosion of radius 11 around the rectangle
+        let explosion = Collider::circle(11.);
+        assert_eq!(
+            engine.query_collider(&explosion, Vec2D::new(20., 0.), 0., |_, _| true),
+            Ok(vec![0, 1, 2, 3])
+        );
+        assert_eq!(
+            engine.query_collider(&explosion, Vec2D::new(20., -5.), 0., |_, _| true),
+            Ok(vec![0, 1, 2])
+        );
+    }
+
+    #[test]
+    fn non_convex_queries() {
+        // A V shaped chain, an L shaped compound and a terrain with a pit
+        let mut engine = row_of_circles();
+        engine.bodies = vec![
+            RigidBody2D::new(
+                Vec2D::zero(),
+                Collider::chain(&[Vec2D::new(-10., 10.), Vec2D::zero(), Vec2D::new(10., 10.)])
+                    .unwrap(),
+                0.,
+            ),
+            RigidBody2D::new(
+                Vec2D::new(30., 0.),
+                Collider::compound(vec![
+                    CompoundChild::new(Collider::rectangle(10., 2.), Vec2D::zero(), 0.),
+                    CompoundChild::new(Collider::rectangle(2., 10.), Vec2D::new(-4., 4.), 0.),
+                ])
+                .unwrap(),
+                0.,
+            ),
+            RigidBody2D::new(
+                Vec2D::new(60., 0.),
+                Collider::heightfield(&[5., 5., 0., 0., 5., 5.], 2.).unwrap(),
+                0.,
+            ),
+        ];
+
+        // In the hollows, away from every piece
+        let in_v = Aabb::new(Vec2D::new(-1., 7.), Vec2D::new(1., 9.));
+        assert_eq!(engine.query_aabb(&in_v, |_, _| true), Ok(vec![]));
+        let in_l = Aabb::new(Vec2D::new(31., 4.), Vec2D::new(33., 6.));
+        assert_eq!(engine.query_aabb(&in_l, |_, _| true), Ok(vec![]));
+        let in_pit = Aabb::new(Vec2D::new(64.5, 1.), Vec2D::new(65.5, 2.));
+        assert_eq!(engine.query_aabb(&in_pit, |_, _| true), Ok(vec![]));
+
+        // Touching the pieces
+        let on_v = Aabb::new(Vec2D::new(4., 4.), Vec2D::new(6., 6.));
+        assert_eq!(engine.query_aabb(&on_v, |_, _| true), Ok(vec![0]));
+        let on_l = Aabb::new(Vec2D::new(31., 0.), Vec2D::new(33., 3.));
+        assert_eq!(engine.query_aabb(&on_l, |_, _| true), Ok(vec![1]));
+        let on_pit = Aabb::new(Vec2D::new(64.5, -1.), Vec2D::new(65.5, 2.));
+        assert_eq!(engine.query_aabb(&on_pit, |_, _| true), Ok(vec![2]));
+
+        // Dropping a ball in the V and in the L: it hits their bottom, not their hull.
+        let ball = Collider::circle(0.5);
+        let (index, hit) = engine
+            .shape_cast(
+                &ball,
+                Vec2D::new(0., 9.),
+                0.,
+                Vec2D::new(0., -9.),
+                |_, _| true,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(index, 0);
+        assert!((hit.fraction * 9. - (9. - 0.5 * 2_f64.sqrt())).abs() < 1e-2);
+
+        let (index, hit) = engine
+            .shape_cast(
+                &ball,
+                Vec2D::new(32., 9.),
+                0.,
+                Vec2D::new(0., -9.),
+                |_, _| true,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(index, 1);
+        assert!((hit.fraction * 9. - 7.5).abs() < 1e-2);
+    }
+
+    /// A user-defined shape whose support function breaks down off the axes
+    #[derive(Debug)]
+    struct Broken;
+
+    impl SupportMap for Broken {
+        fn support(&self, direction: Vec2D) -> Vec2D {
+            if direction.x * direction.y == 0. {
+                Vec2D::new(direction.x.signum(), direction.y.signum())
+            } else {
+                Vec2D::new(f64::NAN, 0.)
+            }
+        }
+    }
+
+    #[test]
+    fn error_policy() {
+        let mut engine = row_of_circles();
+        let broken = Collider::convex(Broken);
+        let query = |engine: &Engine| {
+            engine.query_collider(&broken, Vec2D::new(10.5, 0.5), 0., |_, _| true)
+        };
+
+        assert_eq!(query(&engine), Ok(vec![]));
+
+        engine.error_policy = ErrorPolicy::Log;
+        assert_eq!(query(&engine), Ok(vec![]));
+
+        engine.error_policy = ErrorPolicy::Abort;
+        assert_eq!(query(&engine), Err(CollisionError::InvalidSupportPoint));
+
+        // A ray through a broken body
+        let body = RigidBody2D::new(Vec2D::new(50., 0.), broken.clone(), 1.);
+        engine.bodies.push(body);
+        let ray = Ray::new(Vec2D::new(40., 0.5), Vec2D::new(1., 0.), 20.);
+        let cast = |engine: &Engine| engine.raycast(&ray, RaycastMode::All, |_, _| true);
+
+        assert!(cast(&engine).is_err());
+
+        engine.error_policy = ErrorPolicy::Skip;
+        assert_eq!(cast(&engine), Ok(vec![]));
+    }
+
+    #[test]
+    fn invalid_joints() {
+        let mut engine = row_of_circles();
+        let joint = |a, b| Box::new(DistanceJoint::new(a, b, Vec2D::zero(), Vec2D::zero(), 10.));
+
+        assert_eq!(
+            engine.add_joint(joint(1, 1)),
+            Err(CollisionError::InvalidJoint)
+        );
+        assert_eq!(
+            engine.add_joint(joint(1, 4)),
+            Err(CollisionError::InvalidJoint)
+        );
+        assert!(engine.joints.is_empty());
+
+        // Pushed past the checks, they are left out along with the step going on.
+        engine.add_joint(joint(1, 2)).unwrap();
+        engine.joints.push(joint(3, 3));
+        engine.joints.push(joint(0, 9));
+        engine.bodies[3].velocity = Vec2D::new(10., 0.);
+
+        engine.physics_step(100.).unwrap();
+        assert_eq!(engine.bodies[3].position, Vec2D::new(21., 10.));
+        let tied = (engine.bodies[2].position - engine.bodies[1].position).norm();
+        assert!((tied - 10.).abs() < 1e-6);
+
+        engine.error_policy = ErrorPolicy::Abort;
+        assert_eq!(engine.physics_step(100.), Err(CollisionError::InvalidJoint));
+        assert_eq!(engine.bodies[3].position, Vec2D::new(21., 10.));
+    }
+}
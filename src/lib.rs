@@ -117,7 +117,8 @@ fn run() -> Result<(), JsValue> {
             let fixed_dt = 10.;
 
             while accumulator > fixed_dt {
-                engine.physics_step(fixed_dt);
+                // The demo skips the pairs it fails on, so the step can't abort.
+                engine.physics_step(fixed_dt).unwrap();
 
                 accumulator -= fixed_dt;
             }
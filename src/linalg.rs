@@ -1,8 +1,10 @@
+pub mod error;
 pub mod mat22;
 pub mod matrix;
 pub mod vec2d;
 pub mod vec3d;
 
+pub use crate::linalg::error::*;
 pub use crate::linalg::mat22::*;
 pub use crate::linalg::matrix::*;
 pub use crate::linalg::vec2d::*;
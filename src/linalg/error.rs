@@ -0,0 +1,21 @@
+use std::fmt::Display;
+
+/// Why a linear algebra operation has no result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinalgError {
+    /// The operation needs a square matrix.
+    NotSquare,
+    /// The matrix has no inverse.
+    Singular,
+}
+
+impl Display for LinalgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotSquare => write!(f, "the matrix isn't square"),
+            Self::Singular => write!(f, "the matrix isn't invertible"),
+        }
+    }
+}
+
+impl std::error::Error for LinalgError {}
@@ -1,5 +1,7 @@
 use std::ops::{Add, AddAssign, Index, IndexMut, Mul, MulAssign, Neg, Sub, SubAssign};
 
+use crate::linalg::LinalgError;
+
 #[derive(PartialEq, Clone)]
 pub struct Matrix {
     data: Vec<Vec<f64>>,
@@ -24,6 +26,11 @@ impl Matrix {
         res
     }
 
+    /// The number of rows, then of columns.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.row, self.col)
+    }
+
     pub fn from(data: Vec<Vec<f64>>) -> Self {
         let row = data.len();
         let col = data[0].len();
@@ -54,9 +61,9 @@ impl Matrix {
         res
     }
 
-    pub fn inv(&self) -> Self {
+    pub fn inv(&self) -> Result<Self, LinalgError> {
         if self.row != self.col {
-            panic!("Matrix is not squared.");
+            return Err(LinalgError::NotSquare);
         }
 
         let n = self.row;
@@ -88,7 +95,7 @@ impl Matrix {
             // println!("  After swap: m: {:?}", m);
             // println!("              res: {:?}", res);
             if m[(i, i)] == 0. {
-                panic!("Matrix is not invertible.");
+                return Err(LinalgError::Singular);
             } else {
                 let coef = 1. / m[(i, i)];
                 for k in 0..n {
@@ -129,9 +136,7 @@ impl Matrix {
             }
         }
 
-        println!("{:#?}", m);
-
-        res
+        Ok(res)
     }
 }
 
@@ -361,7 +366,7 @@ impl Mul<&Matrix> for f64 {
 
 #[cfg(test)]
 mod tests {
-    use crate::linalg::Matrix;
+    use crate::linalg::{LinalgError, Matrix};
 
     #[test]
     fn debug() {
@@ -397,10 +402,16 @@ mod tests {
             vec![5.0, -2.0, 2.0],
         ]);
 
-        assert_eq!(a.inv(), a_inv);
+        assert_eq!(a.inv(), Ok(a_inv));
 
         let b = Matrix::from(vec![vec![0., 0., 1.], vec![0., 1., 0.], vec![1., 0., 0.]]);
 
-        assert_eq!(b.inv(), b);
+        assert_eq!(b.inv(), Ok(b));
+
+        let singular = Matrix::from(vec![vec![1., 2.], vec![2., 4.]]);
+        assert_eq!(singular.inv(), Err(LinalgError::Singular));
+
+        let rectangular = Matrix::zeroes(2, 3);
+        assert_eq!(rectangular.inv(), Err(LinalgError::NotSquare));
     }
 }
@@ -48,3 +48,91 @@ pub fn rect(ctx: &CanvasRenderingContext2d, p1: Vec2D, p2: Vec2D, color: &str) {
     let size = p2 - p1;
     ctx.fill_rect(p1.x, p1.y, size.x, size.y)
 }
+
+pub fn outline_rect(ctx: &CanvasRenderingContext2d, p1: Vec2D, p2: Vec2D, color: &str) {
+    ctx.set_stroke_style(&color.into());
+    let size = p2 - p1;
+    ctx.stroke_rect(p1.x, p1.y, size.x, size.y)
+}
+
+/// The stadium around the segment `[p1, p2]`.
+pub fn capsule(
+    ctx: &CanvasRenderingContext2d,
+    p1: Vec2D,
+    p2: Vec2D,
+    radius: f64,
+    color: &str,
+) -> Result<(), JsValue> {
+    let angle = (p2 - p1).y.atan2((p2 - p1).x);
+
+    ctx.set_fill_style(&color.into());
+    ctx.begin_path();
+    ctx.arc(p2.x, p2.y, radius, angle - PI / 2., angle + PI / 2.)?;
+    ctx.arc(p1.x, p1.y, radius, angle + PI / 2., angle + 3. * PI / 2.)?;
+    ctx.close_path();
+    ctx.stroke();
+    ctx.fill();
+
+    Ok(())
+}
+
+/// The counter-clockwise polygon rounded by `radius`.
+pub fn rounded_polygon(
+    ctx: &CanvasRenderingContext2d,
+    vertices: &[Vec2D],
+    radius: f64,
+    color: &str,
+) -> Result<(), JsValue> {
+    let n = vertices.len();
+    let angle = |edge: Vec2D| edge.right().y.atan2(edge.right().x);
+
+    ctx.set_fill_style(&color.into());
+    ctx.begin_path();
+
+    // An arc around each vertex, between the outward normals of its edges
+    for i in 0..n {
+        let prev = vertices[(i + n - 1) % n];
+        let next = vertices[(i + 1) % n];
+        let vertex = vertices[i];
+
+        ctx.arc(
+            vertex.x,
+            vertex.y,
+            radius,
+            angle(vertex - prev),
+            angle(next - vertex),
+        )?;
+    }
+
+    ctx.close_path();
+    ctx.stroke();
+    ctx.fill();
+
+    Ok(())
+}
+
+/// The ellipse with semi-axes `half_width` and `half_height`, rotated by `angle`.
+pub fn ellipse(
+    ctx: &CanvasRenderingContext2d,
+    center: Vec2D,
+    half_width: f64,
+    half_height: f64,
+    angle: f64,
+    color: &str,
+) -> Result<(), JsValue> {
+    ctx.set_fill_style(&color.into());
+    ctx.begin_path();
+    ctx.ellipse(
+        center.x,
+        center.y,
+        half_width,
+        half_height,
+        angle,
+        0.,
+        2. * PI,
+    )?;
+    ctx.stroke();
+    ctx.fill();
+
+    Ok(())
+}
@@ -5,7 +5,12 @@ use derivative::Derivative;
 // use crate::linalg::{Mat22, Vec2D};
 
 use crate::{
-    collision::collider::Collider,
+    collision::{
+        aabb::Aabb,
+        collider::{Collider, PolygonError},
+        error::CollisionError,
+        ray::{Ray, RayHit},
+    },
     linalg::{Mat22, Vec2D},
     rigidbody2d::force_generator::ForceGenerator,
 };
@@ -46,7 +51,7 @@ impl Clone for RigidBody2D {
 
 impl RigidBody2D {
     pub fn new(position: Vec2D, collider: Collider, mass: f64) -> Self {
-        let inertia = collider.get_inertia();
+        let inertia = collider.get_inertia(mass);
         Self {
             position,
             collider,
@@ -59,29 +64,12 @@ impl RigidBody2D {
         }
     }
 
-    pub fn from_polygon(vertices: &Vec<Vec2D>, mass: f64) -> Self {
-        let collider = Collider::PolygonCollider {
-            vertices: vertices.to_vec(),
-        };
-        let inertia = collider.get_inertia();
+    /// A body at the origin shaped as the convex polygon of `vertices`, in world space. See
+    /// `Collider::polygon` for what they must be.
+    pub fn from_polygon(vertices: &[Vec2D], mass: f64) -> Result<Self, PolygonError> {
+        let collider = Collider::polygon(vertices)?;
 
-        // let position = collider.center();
-        let position = Vec2D::zero();
-
-        // collider = Collider::PolygonCollider {
-        //     vertices: vertices.iter().map(|v| *v - position).collect(),
-        // };
-
-        Self {
-            position,
-            collider,
-            velocity: Vec2D::zero(),
-            angle: 0.,
-            angular_velocity: 0.,
-            force_generators: vec![],
-            mass,
-            inertia,
-        }
+        Ok(Self::new(Vec2D::zero(), collider, mass))
     }
 
     pub fn get_mass_inv(&self) -> f64 {
@@ -105,6 +93,12 @@ impl RigidBody2D {
     }
 
     pub fn step(&mut self, dt: f64) {
+        self.integrate_velocity(dt);
+        self.integrate_position(dt);
+    }
+
+    /// Applies the forces of the force generators over `dt`, leaving the pose as it is.
+    pub fn integrate_velocity(&mut self, dt: f64) {
         let mut force_acc = Vec2D::zero();
         let mut torque_acc = 0.;
 
@@ -118,10 +112,14 @@ impl RigidBody2D {
         let acceleration = self.get_mass_inv() * force_acc;
 
         self.velocity += acceleration * dt;
-        self.position += self.velocity * dt;
 
         // This is SUSSSSSS
         self.angular_velocity += torque_acc * dt;
+    }
+
+    /// Moves the body at its current velocities over `dt`.
+    pub fn integrate_position(&mut self, dt: f64) {
+        self.position += self.velocity * dt;
         self.angle += self.angular_velocity * dt;
     }
 
@@ -134,4 +132,132 @@ impl RigidBody2D {
         let rot = Mat22::from_angle(-self.angle);
         rot * point
     }
+
+    /// The box bounding the body in world space.
+    /// Exact for every shape but circles, which don't depend on the angle.
+    pub fn world_aabb(&self) -> Aabb {
+        match &self.collider {
+            Collider::CircleCollider { .. } => self.collider.local_aabb().translate(self.position),
+            Collider::CapsuleCollider {
+                half_length,
+                radius,
+            } => {
+                let ends = [
+                    self.to_global(Vec2D::new(-half_length, 0.)),
+                    self.to_global(Vec2D::new(*half_length, 0.)),
+                ];
+                Aabb::from_points(&ends).expand(*radius)
+            }
+            Collider::EllipseCollider { .. } | Collider::ConvexCollider { .. } => {
+                let extremes: Vec<Vec2D> = [
+                    Vec2D::new(1., 0.),
+                    Vec2D::new(-1., 0.),
+                    Vec2D::new(0., 1.),
+                    Vec2D::new(0., -1.),
+                ]
+                .iter()
+                .map(|dir| self.to_global(self.collider.support(self.to_local(*dir))))
+                .collect();
+                Aabb::from_points(&extremes)
+            }
+            Collider::SegmentCollider { start, end } => {
+                Aabb::from_points(&[self.to_global(*start), self.to_global(*end)])
+            }
+            Collider::PolygonCollider { vertices, radius } => {
+                let world_vertices: Vec<Vec2D> =
+                    vertices.iter().map(|v| self.to_global(*v)).collect();
+                Aabb::from_points(&world_vertices).expand(*radius)
+            }
+            Collider::ChainCollider { vertices, .. } => {
+                let world_vertices: Vec<Vec2D> =
+                    vertices.iter().map(|v| self.to_global(*v)).collect();
+                Aabb::from_points(&world_vertices)
+            }
+            Collider::HeightfieldCollider { .. } => {
+                let aabb = self.collider.local_aabb();
+                let corners = [
+                    aabb.min,
+                    Vec2D::new(aabb.max.x, aabb.min.y),
+                    aabb.max,
+                    Vec2D::new(aabb.min.x, aabb.max.y),
+                ];
+                let corners: Vec<Vec2D> = corners.iter().map(|c| self.to_global(*c)).collect();
+                Aabb::from_points(&corners)
+            }
+            Collider::CompoundCollider { .. } => self
+                .child_bodies()
+                .iter()
+                .map(|(_, child)| child.world_aabb())
+                .reduce(|a, b| a.union(&b))
+                .unwrap(),
+        }
+    }
+
+    /// The children of a compound collider as bodies placed in world space, with their index.
+    /// Other bodies are their only part, without index.
+    pub fn child_bodies(&self) -> Vec<(Option<usize>, RigidBody2D)> {
+        match &self.collider {
+            Collider::CompoundCollider { children } => children
+                .iter()
+                .enumerate()
+                .map(|(index, child)| {
+                    let mut body =
+                        RigidBody2D::new(self.to_global(child.offset), child.collider.clone(), 0.);
+                    body.angle = self.angle + child.angle;
+                    (Some(index), body)
+                })
+                .collect(),
+            _ => vec![(None, self.clone())],
+        }
+    }
+
+    /// The farthest a point of the collider is from the position of the body.
+    pub fn bounding_radius(&self) -> f64 {
+        let aabb = self.collider.local_aabb();
+
+        [
+            aabb.min,
+            Vec2D::new(aabb.max.x, aabb.min.y),
+            aabb.max,
+            Vec2D::new(aabb.min.x, aabb.max.y),
+        ]
+        .iter()
+        .map(|corner| corner.norm())
+        .fold(0., f64::max)
+    }
+
+    /// The box bounding the body during a step of `dt` seconds, assuming no force is applied
+    /// during the step. While it turns, a point can't get further from the boxes at the start and
+    /// at the end than the arc it travels.
+    pub fn swept_aabb(&self, dt: f64) -> Aabb {
+        let mut next = self.clone();
+        next.position += self.velocity * dt;
+        next.angle += self.angular_velocity * dt;
+
+        let arc = self.bounding_radius() * (self.angular_velocity * dt).abs();
+
+        self.world_aabb().union(&next.world_aabb()).expand(arc)
+    }
+
+    pub fn contains_point(&self, point: Vec2D) -> bool {
+        self.collider
+            .contains_point(self.to_local(point - self.position))
+    }
+
+    /// Casts a ray expressed in world space against the collider of the body.
+    pub fn raycast(&self, ray: &Ray) -> Result<Option<RayHit>, CollisionError> {
+        let local_ray = Ray::new(
+            self.to_local(ray.origin - self.position),
+            self.to_local(ray.direction),
+            ray.max_fraction,
+        );
+
+        let hit = self.collider.raycast(&local_ray)?;
+
+        Ok(hit.map(|hit| RayHit {
+            fraction: hit.fraction,
+            point: self.to_global(hit.point),
+            normal: Mat22::from_angle(self.angle) * hit.normal,
+        }))
+    }
 }